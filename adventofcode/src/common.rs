@@ -1 +1,14 @@
+pub mod cache;
 pub mod config;
+pub mod geom;
+pub mod image;
+pub mod interval;
+pub mod math;
+pub mod prop;
+pub mod ratio;
+pub mod readline;
+pub mod rng;
+pub mod search;
+pub mod term;
+pub mod top_k;
+pub mod viz;
@@ -0,0 +1,261 @@
+//! Content-addressed answer cache: answers are stored in files named after a
+//! hash of the day, the part, the solver version, the options the answers
+//! depend on and the input bytes.
+
+use std::{fs, io, path::PathBuf};
+
+use super::config::Options;
+
+pub const DEFAULT_DIR: &str = ".answer-cache";
+/// Enables the cache like `--cache=<dir>` when set.
+pub const DIR_VARIABLE: &str = "ADVENTOFCODE_CACHE";
+
+const FNV_OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+const FNV_PRIME: u64 = 0x0000_0100_0000_01b3;
+
+/// 64 bit FNV-1a.
+pub struct Fnv1a(u64);
+
+impl Fnv1a {
+    pub fn new() -> Self {
+        return Self(FNV_OFFSET_BASIS);
+    }
+
+    pub fn write(&mut self, bytes: &[u8]) {
+        for byte in bytes {
+            self.0 ^= *byte as u64;
+            self.0 = self.0.wrapping_mul(FNV_PRIME);
+        }
+    }
+
+    /// Writes the length first so consecutive fields can't run into each other.
+    pub fn write_field(&mut self, bytes: &[u8]) {
+        self.write(&(bytes.len() as u64).to_le_bytes());
+        self.write(bytes);
+    }
+
+    pub fn finish(&self) -> u64 {
+        return self.0;
+    }
+}
+
+impl Default for Fnv1a {
+    fn default() -> Self {
+        return Self::new();
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Key {
+    pub day: u8,
+    pub part: u8,
+    hash: u64,
+}
+
+impl Key {
+    /// Bumping `solver_version` invalidates the answers of that day.
+    /// `settings` are the options the answers depend on.
+    pub fn new(day: u8, part: u8, solver_version: &str, settings: &str, input: &str) -> Self {
+        let mut hasher = Fnv1a::new();
+        hasher.write(&[day, part]);
+        hasher.write_field(solver_version.as_bytes());
+        hasher.write_field(settings.as_bytes());
+        hasher.write_field(input.as_bytes());
+        return Self {
+            day,
+            part,
+            hash: hasher.finish(),
+        };
+    }
+
+    fn file_name(&self) -> String {
+        return format!("day{:02}-part{}-{:016x}", self.day, self.part, self.hash);
+    }
+
+    /// Whether `name` is one `file_name` could give, so that clearing the cache
+    /// leaves other files of its directory alone.
+    fn is_file_name(name: &str) -> bool {
+        let Some(rest) = name.strip_prefix("day") else {
+            return false;
+        };
+        let Some((day, rest)) = rest.split_once("-part") else {
+            return false;
+        };
+        let Some((part, hash)) = rest.split_once('-') else {
+            return false;
+        };
+        let is_decimal = |text: &str| !text.is_empty() && text.bytes().all(|b| b.is_ascii_digit());
+        let is_hex = |b: u8| b.is_ascii_digit() || (b'a'..=b'f').contains(&b);
+        return day.len() >= 2
+            && is_decimal(day)
+            && is_decimal(part)
+            && hash.len() == 16
+            && hash.bytes().all(is_hex);
+    }
+}
+
+pub struct Cache {
+    dir: PathBuf,
+}
+
+impl Cache {
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        return Self { dir: dir.into() };
+    }
+
+    /// The cache is opt-in: enabled by `--cache[=<dir>]` or the
+    /// `ADVENTOFCODE_CACHE` variable, `--no-cache` disables it.
+    pub fn from_options(options: &Options) -> Option<Self> {
+        if options.has("no-cache") {
+            return None;
+        }
+        if options.has("cache") {
+            return Some(Self::new(options.get("cache").unwrap_or(DEFAULT_DIR)));
+        }
+        return std::env::var(DIR_VARIABLE).ok().map(Self::new);
+    }
+
+    /// The directory used by `cache clear`, which works even without `--cache`.
+    pub fn dir_from_options(options: &Options) -> Self {
+        return match options.get("cache") {
+            Some(dir) => Self::new(dir),
+            None => Self::new(std::env::var(DIR_VARIABLE).unwrap_or(DEFAULT_DIR.to_string())),
+        };
+    }
+
+    pub fn get(&self, key: &Key) -> Option<String> {
+        return fs::read_to_string(self.dir.join(key.file_name())).ok();
+    }
+
+    pub fn put(&self, key: &Key, answer: &str) -> io::Result<()> {
+        fs::create_dir_all(&self.dir)?;
+        return fs::write(self.dir.join(key.file_name()), answer);
+    }
+
+    /// Removes every cached answer, returns how many there were. Files the
+    /// cache didn't write are kept.
+    pub fn clear(&self) -> io::Result<usize> {
+        let entries = match fs::read_dir(&self.dir) {
+            Ok(entries) => entries,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(0),
+            Err(err) => return Err(err),
+        };
+
+        let mut removed = 0;
+        for entry in entries {
+            let path = entry?.path();
+            let is_answer = path
+                .file_name()
+                .and_then(|name| name.to_str())
+                .is_some_and(Key::is_file_name);
+            if path.is_file() && is_answer {
+                fs::remove_file(path)?;
+                removed += 1;
+            }
+        }
+        // only succeeds when nothing else was stored there
+        if removed > 0 {
+            let _ = fs::remove_dir(&self.dir);
+        }
+        return Ok(removed);
+    }
+
+    pub fn dir(&self) -> &PathBuf {
+        return &self.dir;
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::{env, fs};
+
+    use super::{Cache, Fnv1a, Key};
+    use crate::common::config::Options;
+
+    #[test]
+    fn fnv1a_reference_values() {
+        let hash = |bytes: &[u8]| {
+            let mut hasher = Fnv1a::new();
+            hasher.write(bytes);
+            hasher.finish()
+        };
+
+        assert_eq!(hash(b""), 0xcbf29ce484222325);
+        assert_eq!(hash(b"a"), 0xaf63dc4c8601ec8c);
+        assert_eq!(hash(b"foobar"), 0x85944171f73967e8);
+    }
+
+    #[test]
+    fn key_depends_on_every_field() {
+        let key = Key::new(7, 1, "1", "", "$ cd /");
+
+        assert_eq!(key, Key::new(7, 1, "1", "", "$ cd /"));
+        assert_ne!(key, Key::new(8, 1, "1", "", "$ cd /"));
+        assert_ne!(key, Key::new(7, 2, "1", "", "$ cd /"));
+        assert_ne!(key, Key::new(7, 1, "2", "", "$ cd /"));
+        assert_ne!(key, Key::new(7, 1, "1", "", "$ cd /\n"));
+        assert_ne!(key, Key::new(7, 1, "1", "size=4\n", "$ cd /"));
+        assert_ne!(Key::new(7, 1, "1a", "", "b"), Key::new(7, 1, "1", "", "ab"));
+        assert_ne!(Key::new(7, 1, "1", "a", "b"), Key::new(7, 1, "1", "", "ab"));
+    }
+
+    #[test]
+    fn store_and_clear() {
+        let dir = env::temp_dir().join(format!("adventofcode-cache-test-{}", std::process::id()));
+        let cache = Cache::new(&dir);
+        let key = Key::new(1, 2, "1", "", "100\n\n");
+
+        assert_eq!(cache.get(&key), None);
+        cache.put(&key, "45000").unwrap();
+        assert_eq!(cache.get(&key), Some("45000".to_string()));
+        assert_eq!(cache.get(&Key::new(1, 1, "1", "", "100\n\n")), None);
+
+        assert_eq!(cache.clear().unwrap(), 1);
+        assert_eq!(cache.get(&key), None);
+        assert!(!dir.exists());
+        assert_eq!(cache.clear().unwrap(), 0);
+    }
+
+    #[test]
+    fn clear_keeps_other_files() {
+        let dir = env::temp_dir().join(format!("adventofcode-cache-other-{}", std::process::id()));
+        let cache = Cache::new(&dir);
+        cache.put(&Key::new(9, 1, "1", "", "R 4\n"), "13").unwrap();
+        let others = [
+            "daylog.txt",
+            "day-notes.md",
+            "day09-part1-0123456789abcdef.txt",
+            "day09-part1-0123456789ABCDEF",
+            "day9-part1-0123456789abcdef",
+        ];
+        for name in others {
+            fs::write(dir.join(name), "keep").unwrap();
+        }
+
+        assert_eq!(cache.clear().unwrap(), 1);
+        for name in others {
+            assert!(dir.join(name).exists(), "{}", name);
+        }
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn answer_file_names() {
+        let key = Key::new(7, 2, "1", "", "$ cd /");
+        assert!(Key::is_file_name(&key.file_name()));
+        assert!(Key::is_file_name("day25-part1-0000000000000000"));
+        assert!(!Key::is_file_name("day07-part2-000000000000000"));
+        assert!(!Key::is_file_name("day07-partx-0000000000000000"));
+        assert!(!Key::is_file_name("daylog.txt"));
+    }
+
+    #[test]
+    fn opt_in() {
+        let (options, _) = Options::parse(["--cache=/tmp/answers"].map(String::from).into_iter());
+        let cache = Cache::from_options(&options).unwrap();
+        assert_eq!(cache.dir().to_str(), Some("/tmp/answers"));
+
+        let (options, _) = Options::parse(["--cache", "--no-cache"].map(String::from).into_iter());
+        assert!(Cache::from_options(&options).is_none());
+    }
+}
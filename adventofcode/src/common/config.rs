@@ -1,6 +1,8 @@
+use std::collections::HashMap;
 use std::fs;
 use std::io;
 use std::io::Read;
+use std::str::FromStr;
 
 #[derive(Debug)]
 pub struct FilePathConfig {
@@ -10,6 +12,79 @@ pub struct FilePathConfig {
 #[derive(Debug)]
 pub struct FileContentConfig {
     pub content: String,
+    pub options: Options,
+}
+
+/// Arguments of `generate <day> [options]`.
+#[derive(Debug)]
+pub struct GenerateConfig {
+    pub day: u8,
+    pub options: Options,
+}
+
+/// Arguments of `cache <action> [options]`.
+#[derive(Debug)]
+pub struct CacheConfig {
+    pub action: String,
+    pub options: Options,
+}
+
+/// Arguments of `repl [--day=<n>] [file_name]`.
+#[derive(Debug)]
+pub struct ReplConfig {
+    pub file_path: Option<String>,
+    pub options: Options,
+}
+
+/// Command line flags given as `--name` or `--name=value`.
+#[derive(Debug, Default)]
+pub struct Options {
+    values: HashMap<String, Option<String>>,
+}
+
+impl Options {
+    /// Splits `args` into options and positional arguments.
+    pub fn parse(args: impl Iterator<Item = String>) -> (Options, Vec<String>) {
+        let mut options = Options::default();
+        let mut positional = Vec::new();
+
+        for arg in args {
+            match arg.strip_prefix("--") {
+                Some(flag) => match flag.split_once('=') {
+                    Some((name, value)) => options.set(name, Some(value)),
+                    None => options.set(flag, None),
+                },
+                None => positional.push(arg),
+            }
+        }
+
+        return (options, positional);
+    }
+
+    pub fn set(&mut self, name: &str, value: Option<&str>) {
+        self.values
+            .insert(name.to_string(), value.map(|value| value.to_string()));
+    }
+
+    pub fn has(&self, name: &str) -> bool {
+        return self.values.contains_key(name);
+    }
+
+    pub fn get(&self, name: &str) -> Option<&str> {
+        return self.values.get(name).and_then(|value| value.as_deref());
+    }
+
+    /// Parses the value of `--name=value`, `None` when the option is missing.
+    pub fn parse_value<T: FromStr>(&self, name: &str) -> Result<Option<T>, String> {
+        return match self.values.get(name) {
+            None => Ok(None),
+            Some(None) => Err(format!("option --{} expects a value", name)),
+            Some(Some(value)) => match value.parse() {
+                Ok(parsed) => Ok(Some(parsed)),
+                Err(_) => Err(format!("invalid value for --{}: {}", name, value)),
+            },
+        };
+    }
 }
 
 impl FilePathConfig {
@@ -31,18 +106,100 @@ impl FileContentConfig {
     ) -> Result<FileContentConfig, &'static str> {
         args.next();
 
-        let content: String = match args.next() {
+        let (options, positional) = Options::parse(args);
+
+        let content: String = match positional.first() {
             None => { // no file path, use stdin
                 println!("No file path provided, using standard input:");
                 let mut input = String::new();
                 io::stdin().read_to_string(&mut input).map_err(|_| "could not read stdin")?;
-                
+
                 input
             },
 
             Some(path) => fs::read_to_string(path).map_err(|_| "could not read file")?,
         };
 
-        return Ok(FileContentConfig { content });
+        return Ok(FileContentConfig { content, options });
+    }
+}
+
+impl GenerateConfig {
+    pub fn build(mut args: impl Iterator<Item = String>) -> Result<GenerateConfig, &'static str> {
+        args.next();
+        if args.next().as_deref() != Some("generate") {
+            return Err("expected generate command");
+        }
+
+        let (options, positional) = Options::parse(args);
+        let day = match positional.first() {
+            None => return Err("need day argument"),
+            Some(day) => day.parse().map_err(|_| "day should be a number")?,
+        };
+
+        return Ok(GenerateConfig { day, options });
+    }
+}
+
+impl CacheConfig {
+    pub fn build(mut args: impl Iterator<Item = String>) -> Result<CacheConfig, &'static str> {
+        args.next();
+        if args.next().as_deref() != Some("cache") {
+            return Err("expected cache command");
+        }
+
+        let (options, mut positional) = Options::parse(args);
+        if positional.is_empty() {
+            return Err("need cache action");
+        }
+        let action = positional.remove(0);
+
+        return Ok(CacheConfig { action, options });
+    }
+}
+
+impl ReplConfig {
+    pub fn build(mut args: impl Iterator<Item = String>) -> Result<ReplConfig, &'static str> {
+        args.next();
+        if args.next().as_deref() != Some("repl") {
+            return Err("expected repl command");
+        }
+
+        let (options, positional) = Options::parse(args);
+        let file_path = positional.into_iter().next();
+
+        return Ok(ReplConfig { file_path, options });
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{GenerateConfig, Options};
+
+    #[test]
+    fn parse_options() {
+        let args = ["--day=8", "data/08.txt", "--animate", "--fps=x"].map(String::from);
+
+        let (options, positional) = Options::parse(args.into_iter());
+
+        assert_eq!(positional, vec!["data/08.txt"]);
+        assert!(options.has("animate"));
+        assert_eq!(options.get("animate"), None);
+        assert_eq!(options.parse_value::<u8>("day"), Ok(Some(8)));
+        assert_eq!(options.parse_value::<u8>("missing"), Ok(None));
+        assert!(options.parse_value::<u32>("fps").is_err());
+        assert!(options.parse_value::<u32>("animate").is_err());
+    }
+
+    #[test]
+    fn generate_command() {
+        let args = ["aoc", "generate", "--seed=3", "7"].map(String::from);
+
+        let config = GenerateConfig::build(args.into_iter()).unwrap();
+
+        assert_eq!(config.day, 7);
+        assert_eq!(config.options.parse_value::<u64>("seed"), Ok(Some(3)));
+        assert!(GenerateConfig::build(["aoc", "generate"].map(String::from).into_iter()).is_err());
+        assert!(GenerateConfig::build(["aoc", "generate", "x"].map(String::from).into_iter()).is_err());
     }
 }
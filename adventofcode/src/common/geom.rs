@@ -0,0 +1,471 @@
+//! 2D/3D integer points, grid directions and bounding boxes.
+//!
+//! The y axis points down, like the rows of a puzzle input: `Direction::Up`
+//! is `(0, -1)`.
+
+use std::{
+    fmt::Display,
+    hash::Hash,
+    ops::{Add, AddAssign, Mul, Neg, Sub, SubAssign},
+};
+
+/// Integer types usable as point coordinates.
+pub trait Coordinate:
+    Copy + Ord + Hash + Default + Add<Output = Self> + Sub<Output = Self> + Mul<Output = Self>
+{
+    const ZERO: Self;
+    const ONE: Self;
+
+    /// `|self - other|`, without overflowing for unsigned types.
+    fn distance_to(self, other: Self) -> Self {
+        if self > other {
+            return self - other;
+        } else {
+            return other - self;
+        }
+    }
+}
+
+/// Coordinates that can go below zero, required for direction offsets.
+pub trait SignedCoordinate: Coordinate + Neg<Output = Self> {
+    fn signum(self) -> Self {
+        return match self.cmp(&Self::ZERO) {
+            std::cmp::Ordering::Less => -Self::ONE,
+            std::cmp::Ordering::Equal => Self::ZERO,
+            std::cmp::Ordering::Greater => Self::ONE,
+        };
+    }
+}
+
+macro_rules! impl_coordinate {
+    ($($t:ty),*) => {
+        $(
+            impl Coordinate for $t {
+                const ZERO: Self = 0;
+                const ONE: Self = 1;
+            }
+        )*
+    };
+}
+
+impl_coordinate!(u8, u16, u32, u64, usize, i8, i16, i32, i64, isize);
+impl SignedCoordinate for i8 {}
+impl SignedCoordinate for i16 {}
+impl SignedCoordinate for i32 {}
+impl SignedCoordinate for i64 {}
+impl SignedCoordinate for isize {}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, PartialOrd, Ord)]
+pub struct Point2<T> {
+    pub x: T,
+    pub y: T,
+}
+
+impl<T: Coordinate> Point2<T> {
+    pub fn new(x: T, y: T) -> Self {
+        return Self { x, y };
+    }
+
+    pub fn manhattan_distance(&self, other: &Self) -> T {
+        return self.x.distance_to(other.x) + self.y.distance_to(other.y);
+    }
+
+    pub fn chebyshev_distance(&self, other: &Self) -> T {
+        return self.x.distance_to(other.x).max(self.y.distance_to(other.y));
+    }
+}
+
+impl<T: SignedCoordinate> Point2<T> {
+    /// Component-wise sign, a unit step towards `self` from the origin.
+    pub fn signum(&self) -> Self {
+        return Self::new(self.x.signum(), self.y.signum());
+    }
+
+    pub fn step(&self, direction: Direction) -> Self {
+        return *self + direction.offset();
+    }
+
+    pub fn neighbours4(&self) -> [Self; 4] {
+        return Direction::CARDINAL.map(|direction| self.step(direction));
+    }
+
+    pub fn neighbours8(&self) -> [Self; 8] {
+        return Direction::ALL.map(|direction| self.step(direction));
+    }
+}
+
+impl<T: Display> Display for Point2<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        return write!(f, "({}, {})", self.x, self.y);
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, PartialOrd, Ord)]
+pub struct Point3<T> {
+    pub x: T,
+    pub y: T,
+    pub z: T,
+}
+
+impl<T: Coordinate> Point3<T> {
+    pub fn new(x: T, y: T, z: T) -> Self {
+        return Self { x, y, z };
+    }
+
+    pub fn manhattan_distance(&self, other: &Self) -> T {
+        return self.x.distance_to(other.x)
+            + self.y.distance_to(other.y)
+            + self.z.distance_to(other.z);
+    }
+
+    pub fn chebyshev_distance(&self, other: &Self) -> T {
+        return self
+            .x
+            .distance_to(other.x)
+            .max(self.y.distance_to(other.y))
+            .max(self.z.distance_to(other.z));
+    }
+}
+
+impl<T: SignedCoordinate> Point3<T> {
+    pub fn signum(&self) -> Self {
+        return Self::new(self.x.signum(), self.y.signum(), self.z.signum());
+    }
+
+    /// The 6 points sharing a face with `self`.
+    pub fn neighbours6(&self) -> [Self; 6] {
+        let (zero, one) = (T::ZERO, T::ONE);
+        return [
+            Self::new(one, zero, zero),
+            Self::new(-one, zero, zero),
+            Self::new(zero, one, zero),
+            Self::new(zero, -one, zero),
+            Self::new(zero, zero, one),
+            Self::new(zero, zero, -one),
+        ]
+        .map(|offset| *self + offset);
+    }
+}
+
+impl<T: Display> Display for Point3<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        return write!(f, "({}, {}, {})", self.x, self.y, self.z);
+    }
+}
+
+macro_rules! impl_point_ops {
+    ($point:ident, $($field:ident),+) => {
+        impl<T: Coordinate> Add for $point<T> {
+            type Output = Self;
+
+            fn add(self, rhs: Self) -> Self {
+                return Self { $($field: self.$field + rhs.$field),+ };
+            }
+        }
+
+        impl<T: Coordinate> Sub for $point<T> {
+            type Output = Self;
+
+            fn sub(self, rhs: Self) -> Self {
+                return Self { $($field: self.$field - rhs.$field),+ };
+            }
+        }
+
+        impl<T: Coordinate> Mul<T> for $point<T> {
+            type Output = Self;
+
+            fn mul(self, rhs: T) -> Self {
+                return Self { $($field: self.$field * rhs),+ };
+            }
+        }
+
+        impl<T: SignedCoordinate> Neg for $point<T> {
+            type Output = Self;
+
+            fn neg(self) -> Self {
+                return Self { $($field: -self.$field),+ };
+            }
+        }
+
+        impl<T: Coordinate> AddAssign for $point<T> {
+            fn add_assign(&mut self, rhs: Self) {
+                *self = *self + rhs;
+            }
+        }
+
+        impl<T: Coordinate> SubAssign for $point<T> {
+            fn sub_assign(&mut self, rhs: Self) {
+                *self = *self - rhs;
+            }
+        }
+    };
+}
+
+impl_point_ops!(Point2, x, y);
+impl_point_ops!(Point3, x, y, z);
+
+/// The 8 grid directions, in clockwise order starting from `Up`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Direction {
+    Up,
+    UpRight,
+    Right,
+    DownRight,
+    Down,
+    DownLeft,
+    Left,
+    UpLeft,
+}
+
+impl Direction {
+    pub const CARDINAL: [Direction; 4] = [
+        Direction::Up,
+        Direction::Right,
+        Direction::Down,
+        Direction::Left,
+    ];
+
+    pub const ALL: [Direction; 8] = [
+        Direction::Up,
+        Direction::UpRight,
+        Direction::Right,
+        Direction::DownRight,
+        Direction::Down,
+        Direction::DownLeft,
+        Direction::Left,
+        Direction::UpLeft,
+    ];
+
+    pub fn offset<T: SignedCoordinate>(&self) -> Point2<T> {
+        let (zero, one) = (T::ZERO, T::ONE);
+        return match self {
+            Direction::Up => Point2::new(zero, -one),
+            Direction::UpRight => Point2::new(one, -one),
+            Direction::Right => Point2::new(one, zero),
+            Direction::DownRight => Point2::new(one, one),
+            Direction::Down => Point2::new(zero, one),
+            Direction::DownLeft => Point2::new(-one, one),
+            Direction::Left => Point2::new(-one, zero),
+            Direction::UpLeft => Point2::new(-one, -one),
+        };
+    }
+
+    pub fn is_cardinal(&self) -> bool {
+        return self.index().is_multiple_of(2);
+    }
+
+    /// Rotates clockwise by `steps` eighths of a turn, counterclockwise when negative.
+    pub fn rotate(&self, steps: i32) -> Self {
+        return Self::ALL[(self.index() as i32 + steps).rem_euclid(8) as usize];
+    }
+
+    pub fn turn_right(&self) -> Self {
+        return self.rotate(2);
+    }
+
+    pub fn turn_left(&self) -> Self {
+        return self.rotate(-2);
+    }
+
+    pub fn reverse(&self) -> Self {
+        return self.rotate(4);
+    }
+
+    fn index(&self) -> usize {
+        return *self as usize;
+    }
+}
+
+/// Smallest axis-aligned rectangle containing a set of points, bounds included.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BoundingBox<T> {
+    pub min: Point2<T>,
+    pub max: Point2<T>,
+}
+
+impl<T: Coordinate> BoundingBox<T> {
+    pub fn new(point: Point2<T>) -> Self {
+        return Self {
+            min: point,
+            max: point,
+        };
+    }
+
+    /// `None` when there are no points.
+    pub fn from_points<'a>(points: impl IntoIterator<Item = &'a Point2<T>>) -> Option<Self>
+    where
+        T: 'a,
+    {
+        let mut points = points.into_iter();
+        let mut bounds = Self::new(*points.next()?);
+        for point in points {
+            bounds.include(point);
+        }
+        return Some(bounds);
+    }
+
+    pub fn include(&mut self, point: &Point2<T>) {
+        self.min = Point2::new(self.min.x.min(point.x), self.min.y.min(point.y));
+        self.max = Point2::new(self.max.x.max(point.x), self.max.y.max(point.y));
+    }
+
+    pub fn contains(&self, point: &Point2<T>) -> bool {
+        return self.min.x <= point.x
+            && point.x <= self.max.x
+            && self.min.y <= point.y
+            && point.y <= self.max.y;
+    }
+
+    pub fn width(&self) -> T {
+        return self.max.x - self.min.x + T::ONE;
+    }
+
+    pub fn height(&self) -> T {
+        return self.max.y - self.min.y + T::ONE;
+    }
+}
+
+/// Smallest axis-aligned box containing a set of 3D points, bounds included.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BoundingBox3<T> {
+    pub min: Point3<T>,
+    pub max: Point3<T>,
+}
+
+impl<T: Coordinate> BoundingBox3<T> {
+    pub fn new(point: Point3<T>) -> Self {
+        return Self {
+            min: point,
+            max: point,
+        };
+    }
+
+    /// `None` when there are no points.
+    pub fn from_points<'a>(points: impl IntoIterator<Item = &'a Point3<T>>) -> Option<Self>
+    where
+        T: 'a,
+    {
+        let mut points = points.into_iter();
+        let mut bounds = Self::new(*points.next()?);
+        for point in points {
+            bounds.include(point);
+        }
+        return Some(bounds);
+    }
+
+    pub fn include(&mut self, point: &Point3<T>) {
+        self.min = Point3::new(
+            self.min.x.min(point.x),
+            self.min.y.min(point.y),
+            self.min.z.min(point.z),
+        );
+        self.max = Point3::new(
+            self.max.x.max(point.x),
+            self.max.y.max(point.y),
+            self.max.z.max(point.z),
+        );
+    }
+
+    pub fn contains(&self, point: &Point3<T>) -> bool {
+        return self.min.x <= point.x
+            && point.x <= self.max.x
+            && self.min.y <= point.y
+            && point.y <= self.max.y
+            && self.min.z <= point.z
+            && point.z <= self.max.z;
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{BoundingBox, BoundingBox3, Direction, Point2, Point3};
+
+    #[test]
+    fn point_arithmetic() {
+        let a = Point2::new(1, 2);
+        let b = Point2::new(4, -2);
+
+        assert_eq!(a + b, Point2::new(5, 0));
+        assert_eq!(a - b, Point2::new(-3, 4));
+        assert_eq!(-a, Point2::new(-1, -2));
+        assert_eq!(a * 3, Point2::new(3, 6));
+
+        let mut c = a;
+        c += b;
+        c -= Point2::new(1, 1);
+        assert_eq!(c, Point2::new(4, -1));
+
+        assert_eq!(
+            Point3::new(1, 2, 3) + Point3::new(1, 1, 1),
+            Point3::new(2, 3, 4)
+        );
+    }
+
+    #[test]
+    fn distances() {
+        let a = Point2::new(1u32, 7);
+        let b = Point2::new(4u32, 2);
+
+        assert_eq!(a.manhattan_distance(&b), 8);
+        assert_eq!(a.chebyshev_distance(&b), 5);
+
+        let a = Point3::new(-1i64, 0, 3);
+        let b = Point3::new(2i64, -4, 3);
+        assert_eq!(a.manhattan_distance(&b), 7);
+        assert_eq!(a.chebyshev_distance(&b), 4);
+    }
+
+    #[test]
+    fn directions() {
+        assert_eq!(Direction::Up.offset::<i32>(), Point2::new(0, -1));
+        assert_eq!(Direction::DownLeft.offset::<i32>(), Point2::new(-1, 1));
+        assert_eq!(Direction::Up.turn_right(), Direction::Right);
+        assert_eq!(Direction::Up.turn_left(), Direction::Left);
+        assert_eq!(Direction::Left.rotate(1), Direction::UpLeft);
+        assert_eq!(Direction::UpLeft.rotate(1), Direction::Up);
+        assert_eq!(Direction::DownRight.reverse(), Direction::UpLeft);
+        assert!(Direction::CARDINAL.iter().all(Direction::is_cardinal));
+
+        for direction in Direction::ALL {
+            let offset = direction.offset::<i32>();
+            assert_eq!(direction.reverse().offset::<i32>(), -offset);
+            assert_eq!(
+                offset.manhattan_distance(&Point2::default()),
+                if direction.is_cardinal() { 1 } else { 2 }
+            );
+        }
+    }
+
+    #[test]
+    fn neighbours() {
+        let origin: Point2<isize> = Point2::default();
+
+        assert!(origin
+            .neighbours4()
+            .iter()
+            .all(|p| p.manhattan_distance(&origin) == 1));
+        assert!(origin
+            .neighbours8()
+            .iter()
+            .all(|p| p.chebyshev_distance(&origin) == 1));
+        assert_eq!(Point2::new(5, -3).signum(), Point2::new(1, -1));
+        assert_eq!(Point3::new(0i8, 0, 0).neighbours6().len(), 6);
+    }
+
+    #[test]
+    fn bounding_box() {
+        let points = [Point2::new(2, 3), Point2::new(-1, 5), Point2::new(4, 0)];
+
+        let bounds = BoundingBox::from_points(&points).unwrap();
+
+        assert_eq!(bounds.min, Point2::new(-1, 0));
+        assert_eq!(bounds.max, Point2::new(4, 5));
+        assert_eq!((bounds.width(), bounds.height()), (6, 6));
+        assert!(bounds.contains(&Point2::new(0, 0)));
+        assert!(!bounds.contains(&Point2::new(5, 0)));
+        assert_eq!(BoundingBox::<i32>::from_points(&[]), None);
+
+        let bounds = BoundingBox3::from_points(&[Point3::new(1, 1, 1), Point3::new(0, 2, -1)]);
+        assert!(bounds.unwrap().contains(&Point3::new(1, 2, 0)));
+    }
+}
@@ -0,0 +1,267 @@
+//! Image export without external crates: binary PPM (P6) for grids and SVG
+//! for points and polylines.
+
+use std::{
+    fmt::Write as _,
+    fs::File,
+    io::{self, BufWriter, Write},
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Rgb(pub u8, pub u8, pub u8);
+
+impl Rgb {
+    pub const BLACK: Rgb = Rgb(0, 0, 0);
+    pub const WHITE: Rgb = Rgb(255, 255, 255);
+
+    /// Linear interpolation, `t` is clamped to `0.0..=1.0`.
+    pub fn mix(&self, other: &Rgb, t: f64) -> Rgb {
+        let t = t.clamp(0.0, 1.0);
+        let channel = |a: u8, b: u8| (a as f64 + (b as f64 - a as f64) * t).round() as u8;
+        return Rgb(
+            channel(self.0, other.0),
+            channel(self.1, other.1),
+            channel(self.2, other.2),
+        );
+    }
+
+    /// `#rrggbb`
+    pub fn to_hex(&self) -> String {
+        return format!("#{:02x}{:02x}{:02x}", self.0, self.1, self.2);
+    }
+}
+
+/// Maps grid cells to colours.
+pub trait Palette<T> {
+    fn color(&self, cell: &T) -> Rgb;
+}
+
+impl<T, F> Palette<T> for F
+where
+    F: Fn(&T) -> Rgb,
+{
+    fn color(&self, cell: &T) -> Rgb {
+        return self(cell);
+    }
+}
+
+/// Evenly spread colours between `from` (at `min`) and `to` (at `max`).
+pub struct Gradient {
+    pub from: Rgb,
+    pub to: Rgb,
+    pub min: f64,
+    pub max: f64,
+}
+
+impl<T: Copy + Into<f64>> Palette<T> for Gradient {
+    fn color(&self, cell: &T) -> Rgb {
+        let range = self.max - self.min;
+        let t = if range == 0.0 {
+            0.0
+        } else {
+            ((*cell).into() - self.min) / range
+        };
+        return self.from.mix(&self.to, t);
+    }
+}
+
+/// Writes `grid` as a binary PPM, each cell drawn as a `scale` x `scale`
+/// square. Rows shorter than the first one are padded with black.
+pub fn write_ppm<T, P>(
+    out: &mut impl Write,
+    grid: &[Vec<T>],
+    palette: &P,
+    scale: usize,
+) -> io::Result<()>
+where
+    P: Palette<T>,
+{
+    let scale = scale.max(1);
+    let width = grid.first().map_or(0, |row| row.len());
+    write!(out, "P6\n{} {}\n255\n", width * scale, grid.len() * scale)?;
+
+    let mut line: Vec<u8> = Vec::with_capacity(width * scale * 3);
+    for row in grid {
+        line.clear();
+        for col_idx in 0..width {
+            let Rgb(r, g, b) = match row.get(col_idx) {
+                Some(cell) => palette.color(cell),
+                None => Rgb::BLACK,
+            };
+            for _ in 0..scale {
+                line.extend_from_slice(&[r, g, b]);
+            }
+        }
+        for _ in 0..scale {
+            out.write_all(&line)?;
+        }
+    }
+    return out.flush();
+}
+
+pub fn save_ppm<T, P>(path: &str, grid: &[Vec<T>], palette: &P, scale: usize) -> io::Result<()>
+where
+    P: Palette<T>,
+{
+    let mut writer = BufWriter::new(File::create(path)?);
+    return write_ppm(&mut writer, grid, palette, scale);
+}
+
+/// An SVG document built from points and polylines, in user coordinates.
+pub struct Svg {
+    view_box: (f64, f64, f64, f64),
+    background: Option<Rgb>,
+    elements: Vec<String>,
+}
+
+impl Svg {
+    /// `view_box` is `(min_x, min_y, width, height)`, y pointing down.
+    pub fn new(view_box: (f64, f64, f64, f64)) -> Self {
+        return Self {
+            view_box,
+            background: None,
+            elements: Vec::new(),
+        };
+    }
+
+    pub fn background(mut self, color: Rgb) -> Self {
+        self.background = Some(color);
+        return self;
+    }
+
+    pub fn polyline(&mut self, points: &[(f64, f64)], stroke: Rgb, stroke_width: f64) {
+        let mut coords = String::new();
+        for (i, (x, y)) in points.iter().enumerate() {
+            if i > 0 {
+                coords.push(' ');
+            }
+            let _ = write!(coords, "{},{}", x, y);
+        }
+        self.elements.push(format!(
+            "<polyline points=\"{}\" fill=\"none\" stroke=\"{}\" stroke-width=\"{}\" \
+             stroke-linejoin=\"round\" stroke-linecap=\"round\"/>",
+            coords,
+            stroke.to_hex(),
+            stroke_width,
+        ));
+    }
+
+    pub fn points(&mut self, points: &[(f64, f64)], fill: Rgb, radius: f64) {
+        let mut group = format!("<g fill=\"{}\">", fill.to_hex());
+        for (x, y) in points {
+            let _ = write!(group, "<circle cx=\"{}\" cy=\"{}\" r=\"{}\"/>", x, y, radius);
+        }
+        group += "</g>";
+        self.elements.push(group);
+    }
+
+    pub fn text(&mut self, position: (f64, f64), size: f64, fill: Rgb, content: &str) {
+        let escaped = content
+            .replace('&', "&amp;")
+            .replace('<', "&lt;")
+            .replace('>', "&gt;");
+        self.elements.push(format!(
+            "<text x=\"{}\" y=\"{}\" font-size=\"{}\" font-family=\"monospace\" fill=\"{}\">{}</text>",
+            position.0,
+            position.1,
+            size,
+            fill.to_hex(),
+            escaped,
+        ));
+    }
+
+    pub fn save(&self, path: &str) -> io::Result<()> {
+        let mut writer = BufWriter::new(File::create(path)?);
+        writer.write_all(self.to_string().as_bytes())?;
+        return writer.flush();
+    }
+}
+
+impl std::fmt::Display for Svg {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let (min_x, min_y, width, height) = self.view_box;
+        writeln!(
+            f,
+            "<svg xmlns=\"http://www.w3.org/2000/svg\" viewBox=\"{} {} {} {}\">",
+            min_x, min_y, width, height
+        )?;
+        if let Some(background) = self.background {
+            writeln!(
+                f,
+                "<rect x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\" fill=\"{}\"/>",
+                min_x,
+                min_y,
+                width,
+                height,
+                background.to_hex()
+            )?;
+        }
+        for element in &self.elements {
+            writeln!(f, "{}", element)?;
+        }
+        return write!(f, "</svg>");
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{write_ppm, Gradient, Rgb, Svg};
+
+    #[test]
+    fn ppm_header_and_pixels() {
+        let grid = vec![vec![0u8, 9], vec![9, 0]];
+        let palette = Gradient {
+            from: Rgb::BLACK,
+            to: Rgb::WHITE,
+            min: 0.0,
+            max: 9.0,
+        };
+
+        let mut out: Vec<u8> = Vec::new();
+        write_ppm(&mut out, &grid, &palette, 2).unwrap();
+
+        let header = b"P6\n4 4\n255\n";
+        assert_eq!(&out[..header.len()], header);
+        let pixels = &out[header.len()..];
+        assert_eq!(pixels.len(), 4 * 4 * 3);
+        // first row: 2 black pixels then 2 white pixels
+        assert_eq!(&pixels[..12], &[0, 0, 0, 0, 0, 0, 255, 255, 255, 255, 255, 255]);
+        // scaled rows are repeated
+        assert_eq!(&pixels[..12], &pixels[12..24]);
+        assert_eq!(&pixels[24..27], &[255, 255, 255]);
+    }
+
+    #[test]
+    fn closure_palette() {
+        let grid = vec![vec![true, false]];
+        let palette = |visible: &bool| if *visible { Rgb(0, 200, 0) } else { Rgb(50, 50, 50) };
+
+        let mut out: Vec<u8> = Vec::new();
+        write_ppm(&mut out, &grid, &palette, 1).unwrap();
+
+        assert!(out.ends_with(&[0, 200, 0, 50, 50, 50]));
+    }
+
+    #[test]
+    fn color_mix() {
+        assert_eq!(Rgb::BLACK.mix(&Rgb(100, 200, 50), 0.5), Rgb(50, 100, 25));
+        assert_eq!(Rgb::BLACK.mix(&Rgb::WHITE, 2.0), Rgb::WHITE);
+        assert_eq!(Rgb(255, 16, 0).to_hex(), "#ff1000");
+    }
+
+    #[test]
+    fn svg_document() {
+        let mut svg = Svg::new((-1.0, -2.0, 10.0, 5.0)).background(Rgb::WHITE);
+        svg.polyline(&[(0.0, 0.0), (1.5, 2.0)], Rgb(255, 0, 0), 0.1);
+        svg.points(&[(3.0, 4.0)], Rgb::BLACK, 0.5);
+        svg.text((0.0, 1.0), 1.0, Rgb::BLACK, "a < b");
+
+        let doc = svg.to_string();
+
+        assert!(doc.starts_with("<svg xmlns=\"http://www.w3.org/2000/svg\" viewBox=\"-1 -2 10 5\">"));
+        assert!(doc.contains("<polyline points=\"0,0 1.5,2\" fill=\"none\" stroke=\"#ff0000\""));
+        assert!(doc.contains("<circle cx=\"3\" cy=\"4\" r=\"0.5\"/>"));
+        assert!(doc.contains(">a &lt; b</text>"));
+        assert!(doc.ends_with("</svg>"));
+    }
+}
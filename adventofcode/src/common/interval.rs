@@ -0,0 +1,552 @@
+//! Ranges over any ordered type and normalized sets of ranges.
+//!
+//! Both ends of a `Range` are either included or excluded, so closed, half-open
+//! and open ranges can be mixed freely (the difference of two closed ranges is
+//! not closed in general). Types implementing `Discrete`, like the integers,
+//! additionally get lengths and merging of ranges with no value between them.
+
+use std::{cmp::Ordering, fmt::Display};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Range<T> {
+    start: T,
+    end: T,
+    start_inclusive: bool,
+    end_inclusive: bool,
+}
+
+impl<T: Ord + Clone> Range<T> {
+    /// `[start, end]`
+    pub fn closed(start: T, end: T) -> Self {
+        return Self::new(start, true, end, true);
+    }
+
+    /// `[start, end)`
+    pub fn half_open(start: T, end: T) -> Self {
+        return Self::new(start, true, end, false);
+    }
+
+    /// `(start, end)`
+    pub fn open(start: T, end: T) -> Self {
+        return Self::new(start, false, end, false);
+    }
+
+    pub fn new(start: T, start_inclusive: bool, end: T, end_inclusive: bool) -> Self {
+        return Self {
+            start,
+            end,
+            start_inclusive,
+            end_inclusive,
+        };
+    }
+
+    pub fn start(&self) -> &T {
+        return &self.start;
+    }
+
+    pub fn end(&self) -> &T {
+        return &self.end;
+    }
+
+    pub fn is_start_inclusive(&self) -> bool {
+        return self.start_inclusive;
+    }
+
+    pub fn is_end_inclusive(&self) -> bool {
+        return self.end_inclusive;
+    }
+
+    pub fn is_empty(&self) -> bool {
+        return match self.start.cmp(&self.end) {
+            Ordering::Less => false,
+            Ordering::Equal => !(self.start_inclusive && self.end_inclusive),
+            Ordering::Greater => true,
+        };
+    }
+
+    pub fn contains_point(&self, value: &T) -> bool {
+        let after_start = match self.start.cmp(value) {
+            Ordering::Less => true,
+            Ordering::Equal => self.start_inclusive,
+            Ordering::Greater => false,
+        };
+        let before_end = match value.cmp(&self.end) {
+            Ordering::Less => true,
+            Ordering::Equal => self.end_inclusive,
+            Ordering::Greater => false,
+        };
+        return after_start && before_end;
+    }
+
+    /// Whether every value of `other` is in `self`. The empty range is
+    /// contained in any range.
+    pub fn contains(&self, other: &Self) -> bool {
+        return other.is_empty()
+            || (cmp_starts(self, other) != Ordering::Greater
+                && cmp_ends(self, other) != Ordering::Less);
+    }
+
+    pub fn intersects(&self, other: &Self) -> bool {
+        return self.intersection(other).is_some();
+    }
+
+    pub fn intersection(&self, other: &Self) -> Option<Self> {
+        let (start, start_inclusive) = match cmp_starts(self, other) {
+            Ordering::Less => (&other.start, other.start_inclusive),
+            _ => (&self.start, self.start_inclusive),
+        };
+        let (end, end_inclusive) = match cmp_ends(self, other) {
+            Ordering::Greater => (&other.end, other.end_inclusive),
+            _ => (&self.end, self.end_inclusive),
+        };
+
+        let res = Self::new(start.clone(), start_inclusive, end.clone(), end_inclusive);
+        if res.is_empty() {
+            return None;
+        } else {
+            return Some(res);
+        }
+    }
+
+    /// The smallest range covering both, if they overlap or touch without a
+    /// gap (e.g. `[0, 3)` and `[3, 5]`).
+    pub fn union(&self, other: &Self) -> Option<Self> {
+        if self.is_empty() {
+            return Some(other.clone());
+        }
+        if other.is_empty() {
+            return Some(self.clone());
+        }
+
+        let (first, second) = match cmp_starts(self, other) {
+            Ordering::Greater => (other, self),
+            _ => (self, other),
+        };
+        let is_connected = match first.end.cmp(&second.start) {
+            Ordering::Less => false,
+            Ordering::Equal => first.end_inclusive || second.start_inclusive,
+            Ordering::Greater => true,
+        };
+        if !is_connected {
+            return None;
+        }
+
+        let (end, end_inclusive) = match cmp_ends(first, second) {
+            Ordering::Less => (&second.end, second.end_inclusive),
+            _ => (&first.end, first.end_inclusive),
+        };
+        return Some(Self::new(
+            first.start.clone(),
+            first.start_inclusive,
+            end.clone(),
+            end_inclusive,
+        ));
+    }
+
+    /// Values of `self` not in `other`, as zero, one or two ranges.
+    pub fn difference(&self, other: &Self) -> Vec<Self> {
+        if self.is_empty() {
+            return vec![];
+        }
+        if self.intersection(other).is_none() {
+            return vec![self.clone()];
+        }
+
+        let before = Self::new(
+            self.start.clone(),
+            self.start_inclusive,
+            other.start.clone(),
+            !other.start_inclusive,
+        );
+        let after = Self::new(
+            other.end.clone(),
+            !other.end_inclusive,
+            self.end.clone(),
+            self.end_inclusive,
+        );
+
+        return [before, after]
+            .into_iter()
+            .filter_map(|piece| piece.intersection(self))
+            .collect();
+    }
+}
+
+impl<T: Display> Display for Range<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        return write!(
+            f,
+            "{}{}, {}{}",
+            if self.start_inclusive { '[' } else { '(' },
+            self.start,
+            self.end,
+            if self.end_inclusive { ']' } else { ')' },
+        );
+    }
+}
+
+/// Orders by start, an included start comes before an excluded one.
+fn cmp_starts<T: Ord>(a: &Range<T>, b: &Range<T>) -> Ordering {
+    return a
+        .start
+        .cmp(&b.start)
+        .then_with(|| b.start_inclusive.cmp(&a.start_inclusive));
+}
+
+/// Orders by end, an included end comes after an excluded one.
+fn cmp_ends<T: Ord>(a: &Range<T>, b: &Range<T>) -> Ordering {
+    return a
+        .end
+        .cmp(&b.end)
+        .then_with(|| a.end_inclusive.cmp(&b.end_inclusive));
+}
+
+/// Types where each value has a well defined successor and predecessor.
+pub trait Discrete: Ord + Clone {
+    fn successor(&self) -> Option<Self>;
+
+    fn predecessor(&self) -> Option<Self>;
+
+    /// Number of values in `[start, end)`, assuming `start <= end`.
+    fn steps_between(start: &Self, end: &Self) -> u128;
+}
+
+macro_rules! impl_discrete {
+    ($($t:ty),*) => {
+        $(
+            impl Discrete for $t {
+                fn successor(&self) -> Option<Self> {
+                    return self.checked_add(1);
+                }
+
+                fn predecessor(&self) -> Option<Self> {
+                    return self.checked_sub(1);
+                }
+
+                fn steps_between(start: &Self, end: &Self) -> u128 {
+                    return (*end as i128 - *start as i128) as u128;
+                }
+            }
+        )*
+    };
+}
+
+impl_discrete!(u8, u16, u32, u64, usize, i8, i16, i32, i64, isize);
+
+impl<T: Discrete> Range<T> {
+    /// The same values as a closed range, `None` if the range is empty.
+    pub fn to_closed(&self) -> Option<Self> {
+        let start = if self.start_inclusive {
+            self.start.clone()
+        } else {
+            self.start.successor()?
+        };
+        let end = if self.end_inclusive {
+            self.end.clone()
+        } else {
+            self.end.predecessor()?
+        };
+
+        let res = Self::closed(start, end);
+        if res.is_empty() {
+            return None;
+        } else {
+            return Some(res);
+        }
+    }
+
+    /// Number of values in the range.
+    pub fn len(&self) -> u128 {
+        return match self.to_closed() {
+            Some(closed) => T::steps_between(&closed.start, &closed.end) + 1,
+            None => 0,
+        };
+    }
+}
+
+/// Sorted, disjoint and non-touching ranges.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IntervalSet<T> {
+    ranges: Vec<Range<T>>,
+}
+
+impl<T: Ord + Clone> Default for IntervalSet<T> {
+    fn default() -> Self {
+        return Self::new();
+    }
+}
+
+impl<T: Ord + Clone> IntervalSet<T> {
+    pub fn new() -> Self {
+        return Self { ranges: Vec::new() };
+    }
+
+    pub fn ranges(&self) -> &[Range<T>] {
+        return &self.ranges;
+    }
+
+    pub fn is_empty(&self) -> bool {
+        return self.ranges.is_empty();
+    }
+
+    pub fn insert(&mut self, range: Range<T>) {
+        if range.is_empty() {
+            return;
+        }
+
+        let mut merged = range;
+        let mut kept = Vec::with_capacity(self.ranges.len() + 1);
+        for existing in self.ranges.drain(..) {
+            match merged.union(&existing) {
+                Some(union) => merged = union,
+                None => kept.push(existing),
+            }
+        }
+
+        let position = kept
+            .iter()
+            .position(|other| cmp_starts(other, &merged) == Ordering::Greater)
+            .unwrap_or(kept.len());
+        kept.insert(position, merged);
+        self.ranges = kept;
+    }
+
+    pub fn contains_point(&self, value: &T) -> bool {
+        return self.ranges.iter().any(|range| range.contains_point(value));
+    }
+
+    pub fn union(&self, other: &Self) -> Self {
+        let mut res = self.clone();
+        for range in &other.ranges {
+            res.insert(range.clone());
+        }
+        return res;
+    }
+
+    pub fn intersection(&self, other: &Self) -> Self {
+        return self
+            .ranges
+            .iter()
+            .flat_map(|a| other.ranges.iter().filter_map(|b| a.intersection(b)))
+            .collect();
+    }
+
+    pub fn difference(&self, other: &Self) -> Self {
+        let mut remaining = self.ranges.clone();
+        for removed in &other.ranges {
+            remaining = remaining
+                .iter()
+                .flat_map(|range| range.difference(removed))
+                .collect();
+        }
+        return remaining.into_iter().collect();
+    }
+
+    /// Ranges between consecutive members of the set, over the order of `T`:
+    /// for integers a gap like `(12, 13)` holds no value. `to_closed().gaps()`
+    /// only has gaps holding values.
+    pub fn gaps(&self) -> Vec<Range<T>> {
+        return self
+            .ranges
+            .windows(2)
+            .map(|pair| {
+                Range::new(
+                    pair[0].end.clone(),
+                    !pair[0].end_inclusive,
+                    pair[1].start.clone(),
+                    !pair[1].start_inclusive,
+                )
+            })
+            .filter(|gap| !gap.is_empty())
+            .collect();
+    }
+}
+
+impl<T: Discrete> IntervalSet<T> {
+    /// Rewrites every range as a closed range and merges the ones with no
+    /// value between them, e.g. `[0, 2]` and `[3, 5]` into `[0, 5]`.
+    pub fn to_closed(&self) -> Self {
+        let mut res: Vec<Range<T>> = Vec::with_capacity(self.ranges.len());
+        for range in self.ranges.iter().filter_map(Range::to_closed) {
+            match res.last_mut() {
+                Some(last) if last.end.successor().as_ref() == Some(&range.start) => {
+                    last.end = range.end;
+                }
+                _ => res.push(range),
+            }
+        }
+        return Self { ranges: res };
+    }
+
+    /// Total number of values covered by the set.
+    pub fn covered_len(&self) -> u128 {
+        return self.ranges.iter().map(Range::len).sum();
+    }
+}
+
+impl<T: Ord + Clone> FromIterator<Range<T>> for IntervalSet<T> {
+    fn from_iter<I: IntoIterator<Item = Range<T>>>(iter: I) -> Self {
+        let mut set = Self::new();
+        for range in iter {
+            set.insert(range);
+        }
+        return set;
+    }
+}
+
+impl<T: Display> Display for IntervalSet<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{{")?;
+        for (i, range) in self.ranges.iter().enumerate() {
+            if i > 0 {
+                write!(f, " ∪ ")?;
+            }
+            write!(f, "{}", range)?;
+        }
+        return write!(f, "}}");
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{IntervalSet, Range};
+
+    #[test]
+    fn contains_and_intersects() {
+        let outer = Range::closed(2, 8);
+        let inner = Range::closed(3, 7);
+
+        assert!(outer.contains(&inner));
+        assert!(!inner.contains(&outer));
+        assert!(outer.intersects(&inner));
+        assert!(Range::closed(5, 7).intersects(&Range::closed(7, 9)));
+        assert!(!Range::half_open(5, 7).intersects(&Range::closed(7, 9)));
+        assert!(!Range::closed(2, 4).intersects(&Range::closed(6, 8)));
+    }
+
+    #[test]
+    fn emptiness() {
+        assert!(!Range::closed(3, 3).is_empty());
+        assert!(Range::half_open(3, 3).is_empty());
+        assert!(Range::closed(4, 3).is_empty());
+        assert!(!Range::open(3, 4).is_empty()); // not empty for a dense type
+        assert_eq!(Range::open(3, 4).len(), 0);
+    }
+
+    #[test]
+    fn intersection_and_union() {
+        let a = Range::half_open(0, 10);
+        let b = Range::closed(5, 15);
+
+        assert_eq!(a.intersection(&b), Some(Range::half_open(5, 10)));
+        assert_eq!(a.union(&b), Some(Range::closed(0, 15)));
+        assert_eq!(
+            Range::half_open(0, 3).union(&Range::closed(3, 5)),
+            Some(Range::closed(0, 5))
+        );
+        assert_eq!(Range::half_open(0, 3).union(&Range::open(3, 5)), None);
+    }
+
+    #[test]
+    fn difference() {
+        let a = Range::closed(0, 10);
+
+        assert_eq!(
+            a.difference(&Range::closed(3, 5)),
+            vec![Range::half_open(0, 3), Range::new(5, false, 10, true)]
+        );
+        assert_eq!(a.difference(&Range::closed(-5, 20)), vec![]);
+        assert_eq!(a.difference(&Range::closed(20, 30)), vec![a]);
+        assert_eq!(
+            a.difference(&Range::half_open(5, 20)),
+            vec![Range::half_open(0, 5)]
+        );
+    }
+
+    #[test]
+    fn set_normalization() {
+        let set: IntervalSet<i32> = [
+            Range::closed(10, 12),
+            Range::closed(0, 3),
+            Range::half_open(2, 5),
+            Range::closed(13, 14),
+            Range::closed(20, 19),
+        ]
+        .into_iter()
+        .collect();
+
+        assert_eq!(
+            set.ranges(),
+            &[
+                Range::half_open(0, 5),
+                Range::closed(10, 12),
+                Range::closed(13, 14)
+            ]
+        );
+        assert_eq!(set.covered_len(), 5 + 3 + 2);
+        assert_eq!(
+            set.gaps(),
+            vec![Range::half_open(5, 10), Range::open(12, 13)]
+        );
+
+        let closed = set.to_closed();
+        assert_eq!(
+            closed.ranges(),
+            &[Range::closed(0, 4), Range::closed(10, 14)]
+        );
+        assert_eq!(closed.gaps(), vec![Range::open(4, 10)]);
+        assert_eq!(closed.covered_len(), set.covered_len());
+    }
+
+    #[test]
+    fn integer_gaps() {
+        let set: IntervalSet<i32> = [
+            Range::closed(2, 12),
+            Range::closed(13, 20),
+            Range::open(22, 30),
+        ]
+        .into_iter()
+        .collect();
+
+        let gaps = set.gaps();
+        assert_eq!(
+            gaps,
+            vec![Range::open(12, 13), Range::new(20, false, 22, true)]
+        );
+        assert_eq!(gaps.iter().map(Range::len).collect::<Vec<_>>(), vec![0, 2]);
+
+        let gaps = set.to_closed().gaps();
+        assert_eq!(gaps, vec![Range::open(20, 23)]);
+        assert_eq!(gaps[0].to_closed(), Some(Range::closed(21, 22)));
+    }
+
+    #[test]
+    fn set_operations() {
+        let a: IntervalSet<u32> = [Range::closed(0, 10), Range::closed(20, 30)]
+            .into_iter()
+            .collect();
+        let b: IntervalSet<u32> = [Range::closed(5, 25)].into_iter().collect();
+
+        assert_eq!(a.union(&b).ranges(), &[Range::closed(0, 30)]);
+        assert_eq!(
+            a.intersection(&b).ranges(),
+            &[Range::closed(5, 10), Range::closed(20, 25)]
+        );
+        assert_eq!(
+            a.difference(&b).ranges(),
+            &[Range::half_open(0, 5), Range::new(25, false, 30, true)]
+        );
+        assert_eq!(a.difference(&b).covered_len(), 5 + 5);
+        assert!(a.contains_point(&30));
+        assert!(!a.contains_point(&15));
+    }
+
+    #[test]
+    fn display() {
+        assert_eq!(Range::half_open(1, 4).to_string(), "[1, 4)");
+        let set: IntervalSet<u8> = [Range::closed(1, 2), Range::open(4, 6)]
+            .into_iter()
+            .collect();
+        assert_eq!(set.to_string(), "{[1, 2] ∪ (4, 6)}");
+    }
+}
@@ -0,0 +1,348 @@
+//! Number theory helpers: gcd/lcm, extended Euclid, modular arithmetic and the
+//! Chinese Remainder Theorem.
+//!
+//! Everything is overflow-checked, operations that can't be represented return
+//! `MathError::Overflow` instead of wrapping or panicking.
+
+use std::fmt::Display;
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum MathError {
+    Overflow,
+    ZeroModulus,
+    NotInvertible(i128, i128), // value, modulus
+    NoSolution,
+    DivisionByZero,
+}
+
+impl Display for MathError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        return match self {
+            MathError::Overflow => write!(f, "arithmetic overflow"),
+            MathError::ZeroModulus => write!(f, "modulus must be positive"),
+            MathError::NotInvertible(value, modulus) => {
+                write!(f, "{} has no inverse modulo {}", value, modulus)
+            }
+            MathError::NoSolution => write!(f, "congruences have no common solution"),
+            MathError::DivisionByZero => write!(f, "division by zero"),
+        };
+    }
+}
+
+impl From<MathError> for String {
+    fn from(value: MathError) -> Self {
+        return value.to_string();
+    }
+}
+
+pub fn gcd(mut a: u64, mut b: u64) -> u64 {
+    while b != 0 {
+        (a, b) = (b, a % b);
+    }
+    return a;
+}
+
+/// Least common multiple, `lcm(0, n)` is 0.
+pub fn lcm(a: u64, b: u64) -> Result<u64, MathError> {
+    if a == 0 || b == 0 {
+        return Ok(0);
+    }
+    return (a / gcd(a, b)).checked_mul(b).ok_or(MathError::Overflow);
+}
+
+/// Greatest common divisor of all values, 0 for an empty slice.
+pub fn gcd_of(values: &[u64]) -> u64 {
+    return values.iter().fold(0, |acc, value| gcd(acc, *value));
+}
+
+/// Least common multiple of all values, 1 for an empty slice.
+pub fn lcm_of(values: &[u64]) -> Result<u64, MathError> {
+    return values.iter().try_fold(1, |acc, value| lcm(acc, *value));
+}
+
+/// Returns `(g, x, y)` such that `a * x + b * y == g` where `g` is the
+/// non-negative gcd of `a` and `b`.
+pub fn extended_gcd(a: i128, b: i128) -> Result<(i128, i128, i128), MathError> {
+    let (mut old_r, mut r) = (a, b);
+    let (mut old_x, mut x) = (1i128, 0i128);
+    let (mut old_y, mut y) = (0i128, 1i128);
+
+    while r != 0 {
+        let quotient = old_r / r;
+        (old_r, r) = (r, checked_sub(old_r, checked_mul(quotient, r)?)?);
+        (old_x, x) = (x, checked_sub(old_x, checked_mul(quotient, x)?)?);
+        (old_y, y) = (y, checked_sub(old_y, checked_mul(quotient, y)?)?);
+    }
+
+    if old_r < 0 {
+        return Ok((checked_neg(old_r)?, checked_neg(old_x)?, checked_neg(old_y)?));
+    }
+    return Ok((old_r, old_x, old_y));
+}
+
+/// `value` reduced into `0..modulus`.
+pub fn mod_normalize(value: i128, modulus: i128) -> Result<i128, MathError> {
+    if modulus <= 0 {
+        return Err(MathError::ZeroModulus);
+    }
+    return Ok(value.rem_euclid(modulus));
+}
+
+pub fn mod_add(a: i128, b: i128, modulus: i128) -> Result<i128, MathError> {
+    let (a, b) = (mod_normalize(a, modulus)?, mod_normalize(b, modulus)?);
+    // both operands are in 0..modulus, so `modulus - b` can't overflow
+    if a >= modulus - b {
+        return Ok(a - (modulus - b));
+    } else {
+        return Ok(a + b);
+    }
+}
+
+/// `a * b mod modulus`, falls back to double-and-add when the product
+/// doesn't fit in an `i128`.
+pub fn mod_mul(a: i128, b: i128, modulus: i128) -> Result<i128, MathError> {
+    let (mut a, mut b) = (mod_normalize(a, modulus)?, mod_normalize(b, modulus)?);
+
+    if let Some(product) = a.checked_mul(b) {
+        return Ok(product % modulus);
+    }
+
+    let mut res = 0;
+    while b > 0 {
+        if b & 1 == 1 {
+            res = mod_add(res, a, modulus)?;
+        }
+        a = mod_add(a, a, modulus)?;
+        b >>= 1;
+    }
+    return Ok(res);
+}
+
+pub fn mod_pow(base: i128, mut exponent: u128, modulus: i128) -> Result<i128, MathError> {
+    let mut base = mod_normalize(base, modulus)?;
+    let mut res = mod_normalize(1, modulus)?;
+
+    while exponent > 0 {
+        if exponent & 1 == 1 {
+            res = mod_mul(res, base, modulus)?;
+        }
+        base = mod_mul(base, base, modulus)?;
+        exponent >>= 1;
+    }
+    return Ok(res);
+}
+
+/// Modular inverse of `value`, which exists only when it is coprime with `modulus`.
+pub fn mod_inv(value: i128, modulus: i128) -> Result<i128, MathError> {
+    let (g, x, _) = extended_gcd(mod_normalize(value, modulus)?, modulus)?;
+    if g != 1 {
+        return Err(MathError::NotInvertible(value, modulus));
+    }
+    return mod_normalize(x, modulus);
+}
+
+/// Solves the system `x ≡ residue (mod modulus)` for every `(residue, modulus)`
+/// pair. Moduli don't need to be pairwise coprime.
+///
+/// Returns `(x, m)` where `m` is the lcm of the moduli and `x` is the smallest
+/// non-negative solution, every solution is `x + k * m`.
+pub fn crt(congruences: &[(i128, i128)]) -> Result<(i128, i128), MathError> {
+    let mut res: (i128, i128) = (0, 1);
+
+    for &(residue, modulus) in congruences {
+        let (x, m) = res;
+        let residue = mod_normalize(residue, modulus)?;
+        let (g, p, _) = extended_gcd(m, modulus)?;
+
+        let diff = checked_sub(residue, x)?;
+        if diff % g != 0 {
+            return Err(MathError::NoSolution);
+        }
+
+        let reduced_modulus = modulus / g;
+        let combined_modulus = checked_mul(m, reduced_modulus)?;
+        // x + m * k ≡ residue (mod modulus)  <=>  k ≡ (diff / g) * p (mod modulus / g)
+        let k = mod_mul(diff / g, p, reduced_modulus)?;
+        let combined = checked_add(x, checked_mul(m, k)?)?;
+
+        res = (mod_normalize(combined, combined_modulus)?, combined_modulus);
+    }
+
+    return Ok(res);
+}
+
+pub fn checked_add(a: i128, b: i128) -> Result<i128, MathError> {
+    return a.checked_add(b).ok_or(MathError::Overflow);
+}
+
+pub fn checked_sub(a: i128, b: i128) -> Result<i128, MathError> {
+    return a.checked_sub(b).ok_or(MathError::Overflow);
+}
+
+pub fn checked_mul(a: i128, b: i128) -> Result<i128, MathError> {
+    return a.checked_mul(b).ok_or(MathError::Overflow);
+}
+
+pub fn checked_neg(a: i128) -> Result<i128, MathError> {
+    return a.checked_neg().ok_or(MathError::Overflow);
+}
+
+pub fn checked_sum(values: impl IntoIterator<Item = i128>) -> Result<i128, MathError> {
+    return values.into_iter().try_fold(0, checked_add);
+}
+
+pub fn checked_product(values: impl IntoIterator<Item = i128>) -> Result<i128, MathError> {
+    return values.into_iter().try_fold(1, checked_mul);
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    /// xorshift64, enough to spread the property test cases
+    struct Rng(u64);
+
+    impl Rng {
+        fn next(&mut self) -> u64 {
+            self.0 ^= self.0 << 13;
+            self.0 ^= self.0 >> 7;
+            self.0 ^= self.0 << 17;
+            return self.0;
+        }
+
+        fn below(&mut self, bound: u64) -> u64 {
+            return self.next() % bound;
+        }
+    }
+
+    const CASES: usize = 500;
+
+    #[test]
+    fn gcd_lcm_examples() {
+        assert_eq!(gcd(12, 18), 6);
+        assert_eq!(gcd(0, 7), 7);
+        assert_eq!(lcm(4, 6), Ok(12));
+        assert_eq!(lcm(0, 6), Ok(0));
+        assert_eq!(gcd_of(&[]), 0);
+        assert_eq!(gcd_of(&[24, 36, 60]), 12);
+        assert_eq!(lcm_of(&[]), Ok(1));
+        assert_eq!(lcm_of(&[2, 3, 4, 5]), Ok(60));
+        assert_eq!(lcm_of(&[u64::MAX, u64::MAX - 1]), Err(MathError::Overflow));
+    }
+
+    #[test]
+    fn gcd_divides_and_lcm_is_multiple() {
+        let mut rng = Rng(0x9e3779b97f4a7c15);
+        for _ in 0..CASES {
+            let (a, b) = (rng.below(1_000_000) + 1, rng.below(1_000_000) + 1);
+            let g = gcd(a, b);
+            let l = lcm(a, b).unwrap();
+
+            assert_eq!(a % g, 0);
+            assert_eq!(b % g, 0);
+            assert_eq!(l % a, 0);
+            assert_eq!(l % b, 0);
+            assert_eq!(g as u128 * l as u128, a as u128 * b as u128);
+        }
+    }
+
+    #[test]
+    fn extended_gcd_bezout() {
+        let mut rng = Rng(42);
+        for _ in 0..CASES {
+            let a = rng.below(2_000_000) as i128 - 1_000_000;
+            let b = rng.below(2_000_000) as i128 - 1_000_000;
+            let (g, x, y) = extended_gcd(a, b).unwrap();
+
+            assert!(g >= 0);
+            assert_eq!(a * x + b * y, g);
+            assert_eq!(g as u64, gcd(a.unsigned_abs() as u64, b.unsigned_abs() as u64));
+        }
+    }
+
+    #[test]
+    fn mod_pow_matches_repeated_multiplication() {
+        let mut rng = Rng(7);
+        for _ in 0..CASES {
+            let modulus = rng.below(10_000) as i128 + 1;
+            let base = rng.below(1_000_000) as i128 - 500_000;
+            let exponent = rng.below(50) as u128;
+
+            let mut expected = 1 % modulus;
+            for _ in 0..exponent {
+                expected = (expected * base).rem_euclid(modulus);
+            }
+
+            assert_eq!(mod_pow(base, exponent, modulus), Ok(expected));
+        }
+    }
+
+    #[test]
+    fn mod_mul_large_modulus() {
+        let modulus = i128::MAX - 2;
+        let a = i128::MAX - 10;
+        // (m - 8) * (m - 8) ≡ 64 (mod m)
+        assert_eq!(mod_mul(a, a, modulus), Ok(64));
+        assert_eq!(mod_pow(2, 127, i128::MAX), Ok(1));
+    }
+
+    #[test]
+    fn mod_inv_is_inverse() {
+        let mut rng = Rng(1234);
+        for _ in 0..CASES {
+            let modulus = rng.below(100_000) as i128 + 2;
+            let value = rng.below(1_000_000) as i128;
+
+            match mod_inv(value, modulus) {
+                Ok(inv) => assert_eq!((value * inv).rem_euclid(modulus), 1),
+                Err(err) => {
+                    assert_eq!(err, MathError::NotInvertible(value, modulus));
+                    assert_ne!(gcd(value as u64, modulus as u64), 1);
+                }
+            }
+        }
+        assert_eq!(mod_inv(3, 0), Err(MathError::ZeroModulus));
+    }
+
+    #[test]
+    fn crt_examples() {
+        assert_eq!(crt(&[(2, 3), (3, 5), (2, 7)]), Ok((23, 105)));
+        // non-coprime moduli
+        assert_eq!(crt(&[(2, 4), (4, 6)]), Ok((10, 12)));
+        assert_eq!(crt(&[(1, 4), (2, 6)]), Err(MathError::NoSolution));
+        assert_eq!(crt(&[]), Ok((0, 1)));
+    }
+
+    #[test]
+    fn crt_solution_satisfies_every_congruence() {
+        let mut rng = Rng(99);
+        for _ in 0..CASES {
+            let x = rng.below(1_000_000_000) as i128;
+            let congruences: Vec<(i128, i128)> = (0..rng.below(5) + 1)
+                .map(|_| {
+                    let modulus = rng.below(1_000) as i128 + 1;
+                    (x % modulus, modulus)
+                })
+                .collect();
+
+            let (solution, modulus) = crt(&congruences).unwrap();
+
+            let moduli: Vec<u64> = congruences.iter().map(|(_, m)| *m as u64).collect();
+            assert_eq!(modulus as u64, lcm_of(&moduli).unwrap());
+            assert!(0 <= solution && solution < modulus);
+            assert_eq!(solution, x % modulus);
+            for (residue, m) in congruences {
+                assert_eq!(solution % m, residue);
+            }
+        }
+    }
+
+    #[test]
+    fn checked_helpers() {
+        assert_eq!(checked_sum([1, 2, 3]), Ok(6));
+        assert_eq!(checked_sum([i128::MAX, 1]), Err(MathError::Overflow));
+        assert_eq!(checked_product([2, 3, 4]), Ok(24));
+        assert_eq!(checked_product([i128::MAX, 2]), Err(MathError::Overflow));
+        assert_eq!(checked_neg(i128::MIN), Err(MathError::Overflow));
+    }
+}
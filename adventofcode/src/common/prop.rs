@@ -0,0 +1,335 @@
+//! Property based testing without external crates.
+//!
+//! Generators are plain functions drawing values from a [`Source`]. The source
+//! records every choice it hands out, so a failing case is shrunk by replaying
+//! smaller choice sequences: removing choices shortens collections, lowering
+//! them makes numbers smaller. Any composed generator shrinks for free.
+//!
+//! ```
+//! use adventofcode::common::prop;
+//!
+//! prop::check(
+//!     |source| source.vec(0, 20, |source| source.between(0, 100)),
+//!     |numbers| numbers.iter().sum::<usize>() <= numbers.len() * 100,
+//! );
+//! ```
+
+use std::{cmp::Ordering, fmt::Debug};
+
+use super::rng::Rng;
+
+/// Hands out random choices and records them, or replays recorded ones.
+pub struct Source {
+    rng: Option<Rng>,
+    replay: Vec<u64>,
+    choices: Vec<u64>,
+}
+
+impl Source {
+    fn random(seed: u64) -> Self {
+        return Self {
+            rng: Some(Rng::new(seed)),
+            replay: Vec::new(),
+            choices: Vec::new(),
+        };
+    }
+
+    /// Missing choices are taken as zero, the simplest value.
+    fn replay(choices: Vec<u64>) -> Self {
+        return Self {
+            rng: None,
+            replay: choices,
+            choices: Vec::new(),
+        };
+    }
+
+    /// Uniform in `0..bound`.
+    pub fn below(&mut self, bound: u64) -> u64 {
+        assert!(bound > 0, "empty range");
+        let choice = match self.rng.as_mut() {
+            Some(rng) => rng.below(bound),
+            None => self
+                .replay
+                .get(self.choices.len())
+                .map_or(0, |&choice| choice.min(bound - 1)),
+        };
+        self.choices.push(choice);
+        return choice;
+    }
+
+    /// Uniform in `min..=max`, shrinks towards `min`.
+    pub fn between(&mut self, min: usize, max: usize) -> usize {
+        assert!(min <= max, "empty range");
+        return min + self.below((max - min) as u64 + 1) as usize;
+    }
+
+    /// Uniform in `min..=max`, shrinks towards `min`.
+    pub fn between_i64(&mut self, min: i64, max: i64) -> i64 {
+        assert!(min <= max, "empty range");
+        let span = max.abs_diff(min);
+        let offset = match span.checked_add(1) {
+            Some(bound) => self.below(bound),
+            None => self.below(u64::MAX),
+        };
+        return min.wrapping_add(offset as i64);
+    }
+
+    /// Shrinks towards `false`.
+    pub fn bool(&mut self) -> bool {
+        return self.below(2) == 1;
+    }
+
+    /// Shrinks towards the first item.
+    pub fn choose<'a, T>(&mut self, items: &'a [T]) -> &'a T {
+        return &items[self.below(items.len() as u64) as usize];
+    }
+
+    /// Between `min_len` and `max_len` items, shrinks towards fewer items.
+    pub fn vec<T>(
+        &mut self,
+        min_len: usize,
+        max_len: usize,
+        mut item: impl FnMut(&mut Source) -> T,
+    ) -> Vec<T> {
+        assert!(min_len <= max_len, "empty range");
+        // one "one more item?" choice before each optional item, so removing
+        // choices removes whole items
+        let keep_going = ((max_len - min_len) / 2 + 1) as u64 + 1;
+        let mut items = Vec::new();
+        while items.len() < max_len {
+            if items.len() >= min_len && self.below(keep_going) == 0 {
+                break;
+            }
+            items.push(item(self));
+        }
+        return items;
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Config {
+    pub cases: usize,
+    pub seed: u64,
+    /// Upper bound on the replays tried while shrinking.
+    pub max_shrink_runs: usize,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        return Self {
+            cases: 256,
+            seed: 0,
+            max_shrink_runs: 10_000,
+        };
+    }
+}
+
+#[derive(Debug)]
+pub struct Failure<T> {
+    /// Seed of the failing case, reproduce it with `Config { seed, cases: 1, .. }`.
+    pub seed: u64,
+    pub original: T,
+    pub shrunk: T,
+    pub shrink_steps: usize,
+}
+
+/// Checks `property` on `Config::default().cases` generated values, panics
+/// with the shrunk counterexample on failure.
+pub fn check<T, G, P>(generator: G, property: P)
+where
+    T: Debug,
+    G: Fn(&mut Source) -> T,
+    P: Fn(&T) -> bool,
+{
+    if let Err(failure) = check_with(&Config::default(), generator, property) {
+        panic!(
+            "property failed for {:?}\n  (seed {}, shrunk in {} steps from {:?})",
+            failure.shrunk, failure.seed, failure.shrink_steps, failure.original
+        );
+    }
+}
+
+pub fn check_with<T, G, P>(config: &Config, generator: G, property: P) -> Result<(), Failure<T>>
+where
+    G: Fn(&mut Source) -> T,
+    P: Fn(&T) -> bool,
+{
+    for case in 0..config.cases as u64 {
+        let seed = config.seed.wrapping_add(case);
+        let mut source = Source::random(seed);
+        let original = generator(&mut source);
+        if property(&original) {
+            continue;
+        }
+
+        let (shrunk, shrink_steps) = shrink(config, source.choices, &generator, &property);
+        return Err(Failure {
+            seed,
+            original,
+            shrunk,
+            shrink_steps,
+        });
+    }
+    return Ok(());
+}
+
+/// Greedily replaces the failing choices with simpler ones that still fail.
+fn shrink<T, G, P>(config: &Config, choices: Vec<u64>, generator: &G, property: &P) -> (T, usize)
+where
+    G: Fn(&mut Source) -> T,
+    P: Fn(&T) -> bool,
+{
+    let mut best = choices;
+    let mut steps = 0;
+    let mut runs = 0;
+
+    // replays the candidate, the choices actually used are kept on failure
+    let mut try_candidate = |candidate: Vec<u64>, best: &mut Vec<u64>| -> bool {
+        if runs >= config.max_shrink_runs {
+            return false;
+        }
+        runs += 1;
+        let mut source = Source::replay(candidate);
+        let value = generator(&mut source);
+        if property(&value) || shortlex(&source.choices, best) != Ordering::Less {
+            return false;
+        }
+        *best = source.choices;
+        true
+    };
+
+    let mut improved = true;
+    while improved {
+        improved = false;
+
+        for chunk in [8, 4, 2, 1] {
+            let mut start = 0;
+            while start + chunk <= best.len() {
+                let mut candidate = best.clone();
+                candidate.drain(start..start + chunk);
+                if try_candidate(candidate, &mut best) {
+                    improved = true;
+                    steps += 1;
+                } else {
+                    start += 1;
+                }
+            }
+        }
+
+        let mut i = 0;
+        while i < best.len() {
+            // binary search for the smallest choice still failing
+            let (mut low, mut high) = (0, best[i]);
+            while low < high && i < best.len() {
+                let middle = low + (high - low) / 2;
+                let mut candidate = best.clone();
+                candidate[i] = middle;
+                if try_candidate(candidate, &mut best) {
+                    improved = true;
+                    steps += 1;
+                    high = middle;
+                } else {
+                    low = middle + 1;
+                }
+            }
+            i += 1;
+        }
+    }
+
+    let value = generator(&mut Source::replay(best));
+    return (value, steps);
+}
+
+/// Shorter sequences first, then the lexicographically smaller.
+fn shortlex(a: &[u64], b: &[u64]) -> Ordering {
+    return a.len().cmp(&b.len()).then_with(|| a.cmp(b));
+}
+
+#[cfg(test)]
+mod test {
+    use super::{check, check_with, Config, Source};
+
+    #[test]
+    fn passing_property() {
+        check(
+            |source| (source.between(0, 1000), source.between(0, 1000)),
+            |(a, b)| a + b == b + a,
+        );
+    }
+
+    #[test]
+    fn shrinks_numbers() {
+        let failure = check_with(
+            &Config::default(),
+            |source| source.between(0, 1_000_000),
+            |&n| n < 1234,
+        )
+        .unwrap_err();
+
+        assert!(failure.original >= 1234);
+        assert_eq!(failure.shrunk, 1234);
+    }
+
+    #[test]
+    fn shrinks_collections() {
+        let failure = check_with(
+            &Config::default(),
+            |source| source.vec(0, 50, |source| source.between(0, 100)),
+            |numbers| numbers.iter().all(|&n| n < 90),
+        )
+        .unwrap_err();
+
+        assert_eq!(failure.shrunk, vec![90]);
+        assert!(failure.shrink_steps > 0);
+    }
+
+    #[test]
+    fn shrinks_composed_values() {
+        let failure = check_with(
+            &Config::default(),
+            |source| {
+                let words = source.vec(1, 10, |source| {
+                    let letters = source.vec(1, 6, |source| *source.choose(&['a', 'b', 'c']));
+                    letters.into_iter().collect::<String>()
+                });
+                words.join(" ")
+            },
+            |text| !text.contains("cb"),
+        )
+        .unwrap_err();
+
+        assert_eq!(failure.shrunk, "cb");
+    }
+
+    #[test]
+    fn reproducible_from_seed() {
+        let config = Config::default();
+        let generator = |source: &mut Source| source.between_i64(-500, 500);
+        let failure = check_with(&config, generator, |&n| n > -400).unwrap_err();
+
+        let replay = Config {
+            seed: failure.seed,
+            cases: 1,
+            ..config
+        };
+        let again = check_with(&replay, generator, |&n| n > -400).unwrap_err();
+        assert_eq!(again.original, failure.original);
+        assert_eq!(again.shrunk, -500);
+    }
+
+    #[test]
+    #[should_panic(expected = "property failed for 10")]
+    fn check_panics_with_counterexample() {
+        check(|source| source.between(0, 100), |&n| n < 10);
+    }
+
+    #[test]
+    fn replay_clamps_and_pads_choices() {
+        let mut source = Source::replay(vec![100, 1]);
+
+        assert_eq!(source.between(5, 10), 10);
+        assert!(source.bool());
+        assert_eq!(source.below(7), 0);
+        assert_eq!(source.choices, vec![5, 1, 0]);
+    }
+}
@@ -0,0 +1,195 @@
+//! Exact fractions over `i128`, overflow-checked like `common::math`.
+
+use std::{cmp::Ordering, fmt::Display};
+
+use crate::common::math::{checked_add, checked_mul, checked_neg, MathError};
+
+/// Always reduced, with a positive denominator.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Ratio {
+    numerator: i128,
+    denominator: i128,
+}
+
+impl Ratio {
+    pub const ZERO: Ratio = Ratio {
+        numerator: 0,
+        denominator: 1,
+    };
+    pub const ONE: Ratio = Ratio {
+        numerator: 1,
+        denominator: 1,
+    };
+
+    pub fn new(numerator: i128, denominator: i128) -> Result<Ratio, MathError> {
+        if denominator == 0 {
+            return Err(MathError::DivisionByZero);
+        }
+        let g = gcd(numerator, denominator);
+        let (mut numerator, mut denominator) = (numerator / g, denominator / g);
+        if denominator < 0 {
+            numerator = checked_neg(numerator)?;
+            denominator = checked_neg(denominator)?;
+        }
+        return Ok(Ratio {
+            numerator,
+            denominator,
+        });
+    }
+
+    pub fn from_integer(value: i128) -> Ratio {
+        return Ratio {
+            numerator: value,
+            denominator: 1,
+        };
+    }
+
+    pub fn numerator(&self) -> i128 {
+        return self.numerator;
+    }
+
+    pub fn denominator(&self) -> i128 {
+        return self.denominator;
+    }
+
+    pub fn checked_add(self, other: Ratio) -> Result<Ratio, MathError> {
+        return Ratio::new(
+            checked_add(
+                checked_mul(self.numerator, other.denominator)?,
+                checked_mul(other.numerator, self.denominator)?,
+            )?,
+            checked_mul(self.denominator, other.denominator)?,
+        );
+    }
+
+    pub fn checked_sub(self, other: Ratio) -> Result<Ratio, MathError> {
+        return self.checked_add(Ratio {
+            numerator: checked_neg(other.numerator)?,
+            denominator: other.denominator,
+        });
+    }
+
+    pub fn checked_mul(self, other: Ratio) -> Result<Ratio, MathError> {
+        return Ratio::new(
+            checked_mul(self.numerator, other.numerator)?,
+            checked_mul(self.denominator, other.denominator)?,
+        );
+    }
+
+    /// Division by zero is `MathError::DivisionByZero`.
+    pub fn checked_div(self, other: Ratio) -> Result<Ratio, MathError> {
+        return Ratio::new(
+            checked_mul(self.numerator, other.denominator)?,
+            checked_mul(self.denominator, other.numerator)?,
+        );
+    }
+
+    pub fn is_negative(&self) -> bool {
+        return self.numerator < 0;
+    }
+
+    pub fn to_f64(self) -> f64 {
+        return self.numerator as f64 / self.denominator as f64;
+    }
+}
+
+fn gcd(a: i128, b: i128) -> i128 {
+    let (mut a, mut b) = (a.unsigned_abs(), b.unsigned_abs());
+    while b != 0 {
+        (a, b) = (b, a % b);
+    }
+    // 2^127 only for i128::MIN / i128::MIN, which is left unreduced
+    return i128::try_from(a).unwrap_or(1);
+}
+
+impl PartialOrd for Ratio {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        return Some(self.cmp(other));
+    }
+}
+
+impl Ord for Ratio {
+    fn cmp(&self, other: &Self) -> Ordering {
+        return compare(
+            self.numerator,
+            self.denominator,
+            other.numerator,
+            other.denominator,
+        );
+    }
+}
+
+/// Compares `a / b` with `c / d`, for positive `b` and `d`, through their
+/// continued fractions so that nothing overflows.
+fn compare(a: i128, b: i128, c: i128, d: i128) -> Ordering {
+    let (whole, other_whole) = (a.div_euclid(b), c.div_euclid(d));
+    if whole != other_whole {
+        return whole.cmp(&other_whole);
+    }
+    let (rest, other_rest) = (a.rem_euclid(b), c.rem_euclid(d));
+    return match (rest, other_rest) {
+        (0, 0) => Ordering::Equal,
+        (0, _) => Ordering::Less,
+        (_, 0) => Ordering::Greater,
+        // rest / b < other_rest / d when b / rest > d / other_rest
+        _ => compare(d, other_rest, b, rest),
+    };
+}
+
+impl Display for Ratio {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.denominator == 1 {
+            return write!(f, "{}", self.numerator);
+        } else {
+            return write!(f, "{}/{}", self.numerator, self.denominator);
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::Ratio;
+    use crate::common::math::MathError;
+
+    fn ratio(numerator: i128, denominator: i128) -> Ratio {
+        return Ratio::new(numerator, denominator).unwrap();
+    }
+
+    #[test]
+    fn reduced_and_signed() {
+        assert_eq!(ratio(6, -8), ratio(-3, 4));
+        assert_eq!(ratio(-3, 4).denominator(), 4);
+        assert_eq!(ratio(0, -5), Ratio::ZERO);
+        assert_eq!(Ratio::new(1, 0), Err(MathError::DivisionByZero));
+        assert_eq!(ratio(10, 5).to_string(), "2");
+        assert_eq!(ratio(-1, 3).to_string(), "-1/3");
+    }
+
+    #[test]
+    fn arithmetic() {
+        let (half, third) = (ratio(1, 2), ratio(1, 3));
+        assert_eq!(half.checked_add(third), Ok(ratio(5, 6)));
+        assert_eq!(half.checked_sub(third), Ok(ratio(1, 6)));
+        assert_eq!(half.checked_mul(third), Ok(ratio(1, 6)));
+        assert_eq!(half.checked_div(third), Ok(ratio(3, 2)));
+        assert_eq!(
+            half.checked_div(Ratio::ZERO),
+            Err(MathError::DivisionByZero)
+        );
+        assert_eq!(
+            Ratio::from_integer(i128::MAX).checked_add(Ratio::ONE),
+            Err(MathError::Overflow)
+        );
+    }
+
+    #[test]
+    fn ordering() {
+        assert!(ratio(1, 3) < ratio(1, 2));
+        assert!(ratio(-1, 2) < ratio(-1, 3));
+        assert_eq!(ratio(2, 4).cmp(&ratio(1, 2)), std::cmp::Ordering::Equal);
+        assert!(ratio(-1, 2).is_negative());
+        let huge = Ratio::new(i128::MAX, i128::MAX - 1).unwrap();
+        assert!(huge > Ratio::ONE);
+        assert!(Ratio::new(i128::MAX - 2, i128::MAX - 1).unwrap() < huge);
+    }
+}
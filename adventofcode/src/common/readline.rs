@@ -0,0 +1,350 @@
+//! A minimal line editor with history, reading raw terminal input.
+//!
+//! Supports cursor movement (arrows, Home/End, Ctrl-A/E/B/F), Backspace,
+//! Delete, Ctrl-U, history with Up/Down or Ctrl-P/N, Ctrl-C to drop the line
+//! and Ctrl-D on an empty line to quit. When stdin isn't a terminal, lines
+//! are read as they come, without prompt.
+
+use std::{
+    collections::VecDeque,
+    io::{self, BufRead, Read, Write},
+};
+
+use super::term::{self, RawMode};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Key {
+    Char(char),
+    Enter,
+    Backspace,
+    Delete,
+    Left,
+    Right,
+    Up,
+    Down,
+    Home,
+    End,
+    /// Ctrl-U
+    KillLine,
+    /// Ctrl-C
+    Interrupt,
+    /// Ctrl-D
+    EndOfFile,
+    Unknown,
+}
+
+/// Decodes key presses, including escape sequences and UTF-8 characters.
+pub fn parse_keys(bytes: &[u8]) -> Vec<Key> {
+    let mut keys = Vec::new();
+    let mut i = 0;
+    while i < bytes.len() {
+        let (key, size) = match bytes[i] {
+            b'\r' | b'\n' => (Key::Enter, 1),
+            0x7f | 0x08 => (Key::Backspace, 1),
+            0x01 => (Key::Home, 1),
+            0x02 => (Key::Left, 1),
+            0x03 => (Key::Interrupt, 1),
+            0x04 => (Key::EndOfFile, 1),
+            0x05 => (Key::End, 1),
+            0x06 => (Key::Right, 1),
+            0x0e => (Key::Down, 1),
+            0x10 => (Key::Up, 1),
+            0x15 => (Key::KillLine, 1),
+            0x1b => parse_escape(&bytes[i..]),
+            byte if byte < 0x20 => (Key::Unknown, 1),
+            _ => parse_char(&bytes[i..]),
+        };
+        keys.push(key);
+        i += size;
+    }
+    return keys;
+}
+
+/// `ESC [ A`, `ESC O H`, `ESC [ 3 ~`...
+fn parse_escape(bytes: &[u8]) -> (Key, usize) {
+    match bytes.get(1) {
+        Some(b'[') | Some(b'O') => (),
+        _ => return (Key::Unknown, 1),
+    }
+    let params = bytes[2..]
+        .iter()
+        .take_while(|byte| byte.is_ascii_digit() || **byte == b';')
+        .count();
+    let Some(&last) = bytes.get(2 + params) else {
+        return (Key::Unknown, bytes.len());
+    };
+    let key = match (&bytes[2..2 + params], last) {
+        (_, b'A') => Key::Up,
+        (_, b'B') => Key::Down,
+        (_, b'C') => Key::Right,
+        (_, b'D') => Key::Left,
+        (_, b'H') | (b"1", b'~') | (b"7", b'~') => Key::Home,
+        (_, b'F') | (b"4", b'~') | (b"8", b'~') => Key::End,
+        (b"3", b'~') => Key::Delete,
+        _ => Key::Unknown,
+    };
+    return (key, 3 + params);
+}
+
+fn parse_char(bytes: &[u8]) -> (Key, usize) {
+    let size = match bytes[0] {
+        0xc0..=0xdf => 2,
+        0xe0..=0xef => 3,
+        0xf0..=0xf7 => 4,
+        _ => 1,
+    };
+    return match bytes.get(..size).and_then(|sequence| std::str::from_utf8(sequence).ok()) {
+        Some(text) => (text.chars().next().map_or(Key::Unknown, Key::Char), size),
+        None => (Key::Unknown, 1),
+    };
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum Outcome {
+    Editing,
+    Submit(String),
+    Cancel,
+    EndOfFile,
+}
+
+#[derive(Debug, Default)]
+pub struct LineEditor {
+    history: Vec<String>,
+    buffer: Vec<char>,
+    cursor: usize,
+    /// Index in `history` while browsing it, and the line being typed before.
+    browsing: Option<(usize, Vec<char>)>,
+    pending: VecDeque<Key>,
+}
+
+impl LineEditor {
+    pub fn new() -> Self {
+        return Self::default();
+    }
+
+    pub fn history(&self) -> &[String] {
+        return &self.history;
+    }
+
+    /// Blank lines and repeats of the previous line are not kept.
+    pub fn add_history(&mut self, line: &str) {
+        if !line.trim().is_empty() && self.history.last().map(String::as_str) != Some(line) {
+            self.history.push(line.to_string());
+        }
+    }
+
+    pub fn line(&self) -> String {
+        return self.buffer.iter().collect();
+    }
+
+    pub fn handle_key(&mut self, key: Key) -> Outcome {
+        match key {
+            Key::Char(ch) => {
+                self.buffer.insert(self.cursor, ch);
+                self.cursor += 1;
+            }
+            Key::Backspace if self.cursor > 0 => {
+                self.cursor -= 1;
+                self.buffer.remove(self.cursor);
+            }
+            Key::Delete if self.cursor < self.buffer.len() => {
+                self.buffer.remove(self.cursor);
+            }
+            Key::EndOfFile if self.buffer.is_empty() => return Outcome::EndOfFile,
+            Key::EndOfFile => return self.handle_key(Key::Delete),
+            Key::Left => self.cursor = self.cursor.saturating_sub(1),
+            Key::Right => self.cursor = (self.cursor + 1).min(self.buffer.len()),
+            Key::Home => self.cursor = 0,
+            Key::End => self.cursor = self.buffer.len(),
+            Key::KillLine => {
+                self.buffer.drain(..self.cursor);
+                self.cursor = 0;
+            }
+            Key::Up => self.history_previous(),
+            Key::Down => self.history_next(),
+            Key::Enter => {
+                let line = self.line();
+                self.add_history(&line);
+                self.reset();
+                return Outcome::Submit(line);
+            }
+            Key::Interrupt => {
+                self.reset();
+                return Outcome::Cancel;
+            }
+            Key::Backspace | Key::Delete | Key::Unknown => (),
+        }
+        return Outcome::Editing;
+    }
+
+    fn reset(&mut self) {
+        self.buffer.clear();
+        self.cursor = 0;
+        self.browsing = None;
+    }
+
+    fn history_previous(&mut self) {
+        let index = match &self.browsing {
+            Some((0, _)) => return,
+            Some((index, _)) => index - 1,
+            None if self.history.is_empty() => return,
+            None => self.history.len() - 1,
+        };
+        let draft = match self.browsing.take() {
+            Some((_, draft)) => draft,
+            None => self.buffer.clone(),
+        };
+        self.browsing = Some((index, draft));
+        self.set_buffer(self.history[index].chars().collect());
+    }
+
+    fn history_next(&mut self) {
+        let Some((index, draft)) = self.browsing.take() else {
+            return;
+        };
+        if index + 1 < self.history.len() {
+            self.browsing = Some((index + 1, draft));
+            self.set_buffer(self.history[index + 1].chars().collect());
+        } else {
+            self.set_buffer(draft);
+        }
+    }
+
+    fn set_buffer(&mut self, buffer: Vec<char>) {
+        self.buffer = buffer;
+        self.cursor = self.buffer.len();
+    }
+
+    /// The next line, `None` at the end of input.
+    pub fn read_line(&mut self, prompt: &str) -> io::Result<Option<String>> {
+        let raw_mode = if term::is_interactive() {
+            RawMode::blocking()
+        } else {
+            None
+        };
+        let Some(raw_mode) = raw_mode else {
+            let mut line = String::new();
+            return match io::stdin().lock().read_line(&mut line)? {
+                0 => Ok(None),
+                _ => Ok(Some(line.trim_end_matches(['\r', '\n']).to_string())),
+            };
+        };
+
+        let mut stdout = io::stdout().lock();
+        self.redraw(&mut stdout, prompt)?;
+        let res = loop {
+            let key = match self.pending.pop_front() {
+                Some(key) => key,
+                None => {
+                    let mut buf = [0u8; 256];
+                    let size = io::stdin().read(&mut buf)?;
+                    if size == 0 {
+                        break None;
+                    }
+                    // pasted text can hold several lines, keep the rest
+                    self.pending.extend(parse_keys(&buf[..size]));
+                    continue;
+                }
+            };
+            match self.handle_key(key) {
+                Outcome::Editing => self.redraw(&mut stdout, prompt)?,
+                Outcome::Submit(line) => break Some(line),
+                Outcome::Cancel => {
+                    write!(stdout, "^C\r\n")?;
+                    self.redraw(&mut stdout, prompt)?;
+                }
+                Outcome::EndOfFile => break None,
+            }
+        };
+        write!(stdout, "\r\n")?;
+        stdout.flush()?;
+
+        drop(raw_mode);
+        return Ok(res);
+    }
+
+    fn redraw(&self, out: &mut impl Write, prompt: &str) -> io::Result<()> {
+        // start of line, prompt and text, clear the rest, move back to the cursor
+        write!(out, "\r{}{}\x1b[K", prompt, self.line())?;
+        let after_cursor = self.buffer.len() - self.cursor;
+        if after_cursor > 0 {
+            write!(out, "\x1b[{}D", after_cursor)?;
+        }
+        return out.flush();
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{parse_keys, Key, LineEditor, Outcome};
+
+    fn type_keys(editor: &mut LineEditor, bytes: &[u8]) -> Vec<Outcome> {
+        return parse_keys(bytes)
+            .into_iter()
+            .map(|key| editor.handle_key(key))
+            .filter(|outcome| *outcome != Outcome::Editing)
+            .collect();
+    }
+
+    #[test]
+    fn key_decoding() {
+        assert_eq!(
+            parse_keys(b"a\x1b[A\x1b[D\x1b[3~\x1bOH\x7f\r\x03"),
+            vec![
+                Key::Char('a'),
+                Key::Up,
+                Key::Left,
+                Key::Delete,
+                Key::Home,
+                Key::Backspace,
+                Key::Enter,
+                Key::Interrupt,
+            ]
+        );
+        assert_eq!(parse_keys("é".as_bytes()), vec![Key::Char('é')]);
+        assert_eq!(parse_keys(b"\x1b"), vec![Key::Unknown]);
+    }
+
+    #[test]
+    fn editing() {
+        let mut editor = LineEditor::new();
+
+        // type, go left twice, insert, delete at the end
+        let outcomes = type_keys(&mut editor, b":dy 7\x1b[D\x1b[D\x1b[Da\x1b[F\x7f8\r");
+        assert_eq!(outcomes, vec![Outcome::Submit(":day 8".to_string())]);
+
+        let outcomes = type_keys(&mut editor, b"abc\x1b[H\x1b[3~\x05d");
+        assert!(outcomes.is_empty());
+        assert_eq!(editor.line(), "bcd");
+
+        let outcomes = type_keys(&mut editor, b"\x1b[D\x15");
+        assert!(outcomes.is_empty());
+        assert_eq!(editor.line(), "d");
+
+        assert_eq!(type_keys(&mut editor, b"\x03"), vec![Outcome::Cancel]);
+        assert_eq!(editor.line(), "");
+        assert_eq!(type_keys(&mut editor, b"\x04"), vec![Outcome::EndOfFile]);
+    }
+
+    #[test]
+    fn history() {
+        let mut editor = LineEditor::new();
+        type_keys(&mut editor, b":day 7\r:load data/07.txt\r\r:load data/07.txt\r");
+        assert_eq!(editor.history(), [":day 7", ":load data/07.txt"]);
+
+        type_keys(&mut editor, b":sh");
+        type_keys(&mut editor, b"\x1b[A");
+        assert_eq!(editor.line(), ":load data/07.txt");
+        type_keys(&mut editor, b"\x1b[A\x1b[A");
+        assert_eq!(editor.line(), ":day 7");
+        type_keys(&mut editor, b"\x1b[B");
+        assert_eq!(editor.line(), ":load data/07.txt");
+        // back to what was being typed
+        type_keys(&mut editor, b"\x1b[B");
+        assert_eq!(editor.line(), ":sh");
+
+        let outcomes = type_keys(&mut editor, b"\x1b[A\x7f\x7f\x7f\x7f\x7f\x7f08.txt\r");
+        assert_eq!(outcomes, vec![Outcome::Submit(":load data/08.txt".to_string())]);
+        assert_eq!(editor.history().len(), 3);
+    }
+}
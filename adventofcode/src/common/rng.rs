@@ -0,0 +1,110 @@
+//! A small seeded PRNG (SplitMix64) for input generators and randomized tests.
+//! Not suitable for anything security related.
+
+#[derive(Debug, Clone)]
+pub struct Rng {
+    state: u64,
+}
+
+impl Rng {
+    pub fn new(seed: u64) -> Self {
+        return Self { state: seed };
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        return z ^ (z >> 31);
+    }
+
+    /// Uniform in `0..bound`, `bound` must not be zero.
+    pub fn below(&mut self, bound: u64) -> u64 {
+        assert!(bound > 0, "empty range");
+        // rejection sampling removes the modulo bias
+        let zone = u64::MAX - u64::MAX % bound;
+        loop {
+            let value = self.next_u64();
+            if value < zone {
+                return value % bound;
+            }
+        }
+    }
+
+    /// Uniform in `min..=max`.
+    pub fn between(&mut self, min: usize, max: usize) -> usize {
+        assert!(min <= max, "empty range");
+        return match (max - min).checked_add(1) {
+            Some(span) => min + self.below(span as u64) as usize,
+            None => self.next_u64() as usize,
+        };
+    }
+
+    /// Uniform in `[0, 1)`.
+    pub fn next_f64(&mut self) -> f64 {
+        return (self.next_u64() >> 11) as f64 / (1u64 << 53) as f64;
+    }
+
+    pub fn chance(&mut self, probability: f64) -> bool {
+        return self.next_f64() < probability;
+    }
+
+    pub fn choose<'a, T>(&mut self, items: &'a [T]) -> &'a T {
+        return &items[self.below(items.len() as u64) as usize];
+    }
+
+    /// Fisher-Yates shuffle.
+    pub fn shuffle<T>(&mut self, items: &mut [T]) {
+        for i in (1..items.len()).rev() {
+            let j = self.below(i as u64 + 1) as usize;
+            items.swap(i, j);
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::Rng;
+
+    #[test]
+    fn same_seed_same_sequence() {
+        let mut a = Rng::new(42);
+        let mut b = Rng::new(42);
+        let mut c = Rng::new(43);
+
+        let first: Vec<u64> = (0..8).map(|_| a.next_u64()).collect();
+        let second: Vec<u64> = (0..8).map(|_| b.next_u64()).collect();
+        let other: Vec<u64> = (0..8).map(|_| c.next_u64()).collect();
+
+        assert_eq!(first, second);
+        assert_ne!(first, other);
+    }
+
+    #[test]
+    fn ranges() {
+        let mut rng = Rng::new(7);
+        let mut seen = [false; 6];
+        for _ in 0..1000 {
+            let value = rng.between(3, 8);
+            assert!((3..=8).contains(&value));
+            seen[value - 3] = true;
+
+            let f = rng.next_f64();
+            assert!((0.0..1.0).contains(&f));
+        }
+        assert!(seen.iter().all(|&s| s));
+        assert_eq!(rng.between(5, 5), 5);
+    }
+
+    #[test]
+    fn shuffle_is_permutation() {
+        let mut rng = Rng::new(1);
+        let mut items: Vec<u32> = (0..50).collect();
+        rng.shuffle(&mut items);
+
+        assert_ne!(items, (0..50).collect::<Vec<u32>>());
+        items.sort();
+        assert_eq!(items, (0..50).collect::<Vec<u32>>());
+    }
+}
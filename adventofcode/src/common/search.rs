@@ -0,0 +1,319 @@
+//! Shortest path searches over implicit graphs.
+//!
+//! Graphs are never built up front: each search takes a `neighbours` callback
+//! returning the successors of a node, so grids, state machines or any other
+//! `Eq + Hash + Clone` node type (e.g. `day09::rope::Point`) can be explored.
+
+use std::{
+    cmp::Reverse,
+    collections::{BinaryHeap, HashMap, VecDeque},
+    hash::Hash,
+    ops::Add,
+};
+
+/// Nodes reached by a search, with their cost and the node they were reached from.
+#[derive(Debug)]
+pub struct SearchResult<N, C> {
+    reached: HashMap<N, (Option<N>, C)>,
+    goal: Option<N>,
+}
+
+impl<N, C> SearchResult<N, C>
+where
+    N: Eq + Hash + Clone,
+    C: Copy,
+{
+    /// The first node accepted by the goal predicate, if any.
+    pub fn goal(&self) -> Option<&N> {
+        return self.goal.as_ref();
+    }
+
+    /// Cost from the nearest start node to the goal.
+    pub fn goal_cost(&self) -> Option<C> {
+        return self.goal.as_ref().and_then(|goal| self.cost(goal));
+    }
+
+    /// Best known cost from the nearest start node to `node`.
+    ///
+    /// When the search stopped early on a goal, only nodes settled before the
+    /// goal are guaranteed to hold their optimal cost.
+    pub fn cost(&self, node: &N) -> Option<C> {
+        return self.reached.get(node).map(|(_, cost)| *cost);
+    }
+
+    /// Path from a start node to the goal, both included.
+    pub fn path(&self) -> Option<Vec<N>> {
+        return self.goal.as_ref().and_then(|goal| self.path_to(goal));
+    }
+
+    /// Path from a start node to `node`, both included.
+    pub fn path_to(&self, node: &N) -> Option<Vec<N>> {
+        let mut path = vec![node.clone()];
+        let mut current = self.reached.get(node)?;
+
+        while let (Some(parent), _) = current {
+            path.push(parent.clone());
+            current = &self.reached[parent];
+        }
+
+        path.reverse();
+        return Some(path);
+    }
+
+    pub fn reached_count(&self) -> usize {
+        return self.reached.len();
+    }
+
+    pub fn reached(&self) -> impl Iterator<Item = (&N, C)> {
+        return self.reached.iter().map(|(node, (_, cost))| (node, *cost));
+    }
+}
+
+/// Breadth-first search where every edge costs 1.
+///
+/// Stops at the first node for which `is_goal` returns true, pass `|_| false`
+/// to explore everything reachable from `starts`.
+pub fn bfs<N, I, F, G>(
+    starts: impl IntoIterator<Item = N>,
+    mut neighbours: F,
+    mut is_goal: G,
+) -> SearchResult<N, usize>
+where
+    N: Eq + Hash + Clone,
+    I: IntoIterator<Item = N>,
+    F: FnMut(&N) -> I,
+    G: FnMut(&N) -> bool,
+{
+    let mut reached: HashMap<N, (Option<N>, usize)> = HashMap::new();
+    let mut queue: VecDeque<(N, usize)> = VecDeque::new();
+
+    for start in starts {
+        if !reached.contains_key(&start) {
+            reached.insert(start.clone(), (None, 0));
+            queue.push_back((start, 0));
+        }
+    }
+
+    while let Some((node, cost)) = queue.pop_front() {
+        if is_goal(&node) {
+            return SearchResult {
+                reached,
+                goal: Some(node),
+            };
+        }
+
+        for next in neighbours(&node) {
+            if !reached.contains_key(&next) {
+                reached.insert(next.clone(), (Some(node.clone()), cost + 1));
+                queue.push_back((next, cost + 1));
+            }
+        }
+    }
+
+    return SearchResult {
+        reached,
+        goal: None,
+    };
+}
+
+/// Dijkstra's algorithm, `neighbours` returns each successor with the cost of
+/// the edge leading to it. Edge costs must not be negative.
+pub fn dijkstra<N, C, I, F, G>(
+    starts: impl IntoIterator<Item = N>,
+    neighbours: F,
+    is_goal: G,
+) -> SearchResult<N, C>
+where
+    N: Eq + Hash + Clone,
+    C: Copy + Ord + Add<Output = C> + Default,
+    I: IntoIterator<Item = (N, C)>,
+    F: FnMut(&N) -> I,
+    G: FnMut(&N) -> bool,
+{
+    return astar(starts, neighbours, |_| C::default(), is_goal);
+}
+
+/// A* search. `heuristic` estimates the remaining cost to a goal and must
+/// never overestimate it for the result to be optimal.
+pub fn astar<N, C, I, F, H, G>(
+    starts: impl IntoIterator<Item = N>,
+    mut neighbours: F,
+    mut heuristic: H,
+    mut is_goal: G,
+) -> SearchResult<N, C>
+where
+    N: Eq + Hash + Clone,
+    C: Copy + Ord + Add<Output = C> + Default,
+    I: IntoIterator<Item = (N, C)>,
+    F: FnMut(&N) -> I,
+    H: FnMut(&N) -> C,
+    G: FnMut(&N) -> bool,
+{
+    let mut reached: HashMap<N, (Option<N>, C)> = HashMap::new();
+    // the heap holds indices into `nodes` so that `N` doesn't need to be `Ord`
+    let mut nodes: Vec<N> = Vec::new();
+    let mut heap: BinaryHeap<Reverse<(C, C, usize)>> = BinaryHeap::new();
+
+    for start in starts {
+        if !reached.contains_key(&start) {
+            let estimate = heuristic(&start);
+            reached.insert(start.clone(), (None, C::default()));
+            heap.push(Reverse((estimate, C::default(), nodes.len())));
+            nodes.push(start);
+        }
+    }
+
+    while let Some(Reverse((_, cost, index))) = heap.pop() {
+        let node = nodes[index].clone();
+        if reached[&node].1 < cost {
+            // a cheaper path to this node was pushed after this one
+            continue;
+        }
+
+        if is_goal(&node) {
+            return SearchResult {
+                reached,
+                goal: Some(node),
+            };
+        }
+
+        for (next, edge_cost) in neighbours(&node) {
+            let next_cost = cost + edge_cost;
+            let is_better = match reached.get(&next) {
+                Some((_, known_cost)) => next_cost < *known_cost,
+                None => true,
+            };
+
+            if is_better {
+                let estimate = next_cost + heuristic(&next);
+                reached.insert(next.clone(), (Some(node.clone()), next_cost));
+                heap.push(Reverse((estimate, next_cost, nodes.len())));
+                nodes.push(next);
+            }
+        }
+    }
+
+    return SearchResult {
+        reached,
+        goal: None,
+    };
+}
+
+#[cfg(test)]
+mod test {
+    const MAZE: &str = "\
+S.#.....
+.##.###.
+....#E..
+.####.#.
+........
+";
+
+    type Position = (isize, isize);
+
+    fn parse_maze(input: &str) -> (Vec<Vec<u8>>, Position, Position) {
+        let grid: Vec<Vec<u8>> = input.lines().map(|line| line.bytes().collect()).collect();
+        let find = |target: u8| {
+            for (y, row) in grid.iter().enumerate() {
+                if let Some(x) = row.iter().position(|ch| *ch == target) {
+                    return (x as isize, y as isize);
+                }
+            }
+            panic!("no {} in maze", target as char);
+        };
+
+        let (start, end) = (find(b'S'), find(b'E'));
+        return (grid, start, end);
+    }
+
+    fn open_neighbours(grid: &[Vec<u8>], (x, y): (isize, isize)) -> Vec<(isize, isize)> {
+        return [(0, -1), (-1, 0), (0, 1), (1, 0)]
+            .into_iter()
+            .map(|(dx, dy)| (x + dx, y + dy))
+            .filter(|&(x, y)| {
+                y >= 0
+                    && x >= 0
+                    && (y as usize) < grid.len()
+                    && (x as usize) < grid[y as usize].len()
+                    && grid[y as usize][x as usize] != b'#'
+            })
+            .collect();
+    }
+
+    #[test]
+    fn bfs_maze() {
+        let (grid, start, end) = parse_maze(MAZE);
+
+        let res = super::bfs([start], |&p| open_neighbours(&grid, p), |p| *p == end);
+
+        assert_eq!(res.goal(), Some(&end));
+        assert_eq!(res.goal_cost(), Some(11));
+        let path = res.path().unwrap();
+        assert_eq!(path.len(), 12);
+        assert_eq!(path.first(), Some(&start));
+        assert_eq!(path.last(), Some(&end));
+        for step in path.windows(2) {
+            let (a, b) = (step[0], step[1]);
+            assert_eq!((a.0 - b.0).abs() + (a.1 - b.1).abs(), 1);
+        }
+    }
+
+    #[test]
+    fn bfs_unreachable_explores_everything() {
+        let (grid, start, _) = parse_maze(MAZE);
+
+        let res = super::bfs([start], |&p| open_neighbours(&grid, p), |_| false);
+
+        assert_eq!(res.goal(), None);
+        assert_eq!(res.path(), None);
+        let open_count = MAZE.bytes().filter(|ch| b".SE".contains(ch)).count();
+        assert_eq!(res.reached_count(), open_count);
+    }
+
+    #[test]
+    fn bfs_multiple_starts() {
+        let (grid, start, end) = parse_maze(MAZE);
+
+        let res = super::bfs(
+            [start, (7, 4)],
+            |&p| open_neighbours(&grid, p),
+            |p| *p == end,
+        );
+
+        assert_eq!(res.goal_cost(), Some(4));
+        assert_eq!(res.path().unwrap().first(), Some(&(7, 4)));
+    }
+
+    #[test]
+    fn dijkstra_prefers_cheap_edges() {
+        // 0 -> 1 -> 2 -> 3 costs 3, 0 -> 3 costs 10
+        let edges = |n: &u32| match n {
+            0 => vec![(1, 1), (3, 10)],
+            1 => vec![(2, 1)],
+            2 => vec![(3, 1)],
+            _ => vec![],
+        };
+
+        let res = super::dijkstra([0u32], edges, |n| *n == 3);
+
+        assert_eq!(res.goal_cost(), Some(3u32));
+        assert_eq!(res.path(), Some(vec![0, 1, 2, 3]));
+    }
+
+    #[test]
+    fn astar_matches_bfs() {
+        let (grid, start, end) = parse_maze(MAZE);
+        let manhattan = |p: &(isize, isize)| ((p.0 - end.0).abs() + (p.1 - end.1).abs()) as usize;
+
+        let res = super::astar(
+            [start],
+            |&p| open_neighbours(&grid, p).into_iter().map(|n| (n, 1usize)),
+            manhattan,
+            |p| *p == end,
+        );
+        let bfs_res = super::bfs([start], |&p| open_neighbours(&grid, p), |p| *p == end);
+
+        assert_eq!(res.goal_cost(), bfs_res.goal_cost());
+        assert!(res.reached_count() <= bfs_res.reached_count());
+    }
+}
@@ -0,0 +1,56 @@
+//! Terminal control without external crates. Uses `stty` since std has no
+//! way to change terminal settings.
+
+use std::{
+    io::{self, IsTerminal},
+    process::{Command, Stdio},
+};
+
+/// Both stdin and stdout are terminals.
+pub fn is_interactive() -> bool {
+    return io::stdin().is_terminal() && io::stdout().is_terminal();
+}
+
+/// Unbuffered, non-echoing stdin for as long as it lives.
+pub struct RawMode {
+    saved_settings: String,
+}
+
+impl RawMode {
+    /// Reads return at once with whatever was typed, possibly nothing.
+    pub fn non_blocking() -> Option<Self> {
+        return Self::enable(&["-icanon", "-echo", "min", "0", "time", "0"]);
+    }
+
+    /// Reads wait for at least one key, Ctrl-C is read as a key instead of
+    /// interrupting the program.
+    pub fn blocking() -> Option<Self> {
+        return Self::enable(&["-icanon", "-echo", "-isig", "min", "1", "time", "0"]);
+    }
+
+    fn enable(settings: &[&str]) -> Option<Self> {
+        let saved_settings = stty(&["-g"])?;
+        stty(settings)?;
+        return Some(Self {
+            saved_settings: saved_settings.trim().to_string(),
+        });
+    }
+}
+
+impl Drop for RawMode {
+    fn drop(&mut self) {
+        let _ = stty(&[&self.saved_settings]);
+    }
+}
+
+fn stty(args: &[&str]) -> Option<String> {
+    let output = Command::new("stty")
+        .args(args)
+        .stdin(Stdio::inherit())
+        .output()
+        .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    return String::from_utf8(output.stdout).ok();
+}
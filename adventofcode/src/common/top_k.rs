@@ -0,0 +1,171 @@
+//! Streaming selection of the `k` greatest items with a bounded min-heap:
+//! O(n log k) time and O(k) memory, without collecting the input.
+
+use std::{
+    cmp::{Ordering, Reverse},
+    collections::BinaryHeap,
+};
+
+struct Entry<K, T> {
+    key: K,
+    /// Push order, so equal keys keep the first pushed items.
+    seq: usize,
+    item: T,
+}
+
+impl<K: Ord, T> Ord for Entry<K, T> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        return self.key
+            .cmp(&other.key)
+            .then_with(|| other.seq.cmp(&self.seq));
+    }
+}
+
+impl<K: Ord, T> PartialOrd for Entry<K, T> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        return Some(self.cmp(other));
+    }
+}
+
+impl<K: Ord, T> PartialEq for Entry<K, T> {
+    fn eq(&self, other: &Self) -> bool {
+        return self.cmp(other) == Ordering::Equal;
+    }
+}
+
+impl<K: Ord, T> Eq for Entry<K, T> {}
+
+/// Keeps the `k` items with the greatest keys pushed so far. Among equal
+/// keys, the items pushed first win.
+pub struct TopK<K, T = ()> {
+    k: usize,
+    heap: BinaryHeap<Reverse<Entry<K, T>>>,
+    pushed: usize,
+}
+
+impl<K: Ord, T> TopK<K, T> {
+    pub fn new(k: usize) -> Self {
+        return Self {
+            k,
+            heap: BinaryHeap::with_capacity(k),
+            pushed: 0,
+        };
+    }
+
+    pub fn push(&mut self, key: K, item: T) {
+        let entry = Entry {
+            key,
+            seq: self.pushed,
+            item,
+        };
+        self.pushed += 1;
+
+        if self.heap.len() < self.k {
+            self.heap.push(Reverse(entry));
+        } else if let Some(mut smallest) = self.heap.peek_mut() {
+            if entry > smallest.0 {
+                *smallest = Reverse(entry);
+            }
+        }
+    }
+
+    /// Number of items kept, at most `k`.
+    pub fn len(&self) -> usize {
+        return self.heap.len();
+    }
+
+    pub fn is_empty(&self) -> bool {
+        return self.heap.is_empty();
+    }
+
+    /// Greatest keys first, equal keys in push order.
+    pub fn into_sorted_vec(self) -> Vec<(K, T)> {
+        // ascending order of Reverse is descending order of entries
+        return self.heap
+            .into_sorted_vec()
+            .into_iter()
+            .map(|Reverse(entry)| (entry.key, entry.item))
+            .collect();
+    }
+}
+
+/// The `k` greatest items, greatest first. Returns every item when there are
+/// fewer than `k`.
+pub fn top_k<T: Ord>(items: impl IntoIterator<Item = T>, k: usize) -> Vec<T> {
+    let mut selector = TopK::new(k);
+    for item in items {
+        selector.push(item, ());
+    }
+    return selector.into_sorted_vec().into_iter().map(|(item, _)| item).collect();
+}
+
+/// The `k` items with the greatest keys, greatest first, equal keys in input
+/// order.
+pub fn top_k_by_key<T, K: Ord>(
+    items: impl IntoIterator<Item = T>,
+    k: usize,
+    mut key: impl FnMut(&T) -> K,
+) -> Vec<T> {
+    let mut selector = TopK::new(k);
+    for item in items {
+        selector.push(key(&item), item);
+    }
+    return selector.into_sorted_vec().into_iter().map(|(_, item)| item).collect();
+}
+
+#[cfg(test)]
+mod test {
+    use std::cmp::Reverse;
+
+    use super::{top_k, top_k_by_key, TopK};
+    use crate::common::prop;
+
+    #[test]
+    fn greatest_first() {
+        assert_eq!(top_k([5, 1, 9, 3, 7], 3), vec![9, 7, 5]);
+        assert_eq!(top_k([5, 1, 9], 0), Vec::<i32>::new());
+        assert_eq!(top_k(Vec::<i32>::new(), 3), Vec::<i32>::new());
+    }
+
+    #[test]
+    fn k_larger_than_input() {
+        assert_eq!(top_k([2, 8, 4], 10), vec![8, 4, 2]);
+    }
+
+    #[test]
+    fn equal_keys_keep_input_order() {
+        let items = [("a", 3), ("b", 5), ("c", 3), ("d", 5), ("e", 3)];
+
+        let top = top_k_by_key(items, 3, |(_, value)| *value);
+
+        assert_eq!(top, vec![("b", 5), ("d", 5), ("a", 3)]);
+    }
+
+    #[test]
+    fn bounded_size() {
+        let mut selector = TopK::new(2);
+        for value in 0..100 {
+            selector.push(value % 7, value);
+            assert!(selector.len() <= 2);
+        }
+        assert_eq!(selector.into_sorted_vec(), vec![(6, 6), (6, 13)]);
+    }
+
+    #[test]
+    fn same_as_stable_sort() {
+        prop::check(
+            |source| {
+                let k = source.between(0, 12);
+                (k, source.vec(0, 40, |source| source.between(0, 10)))
+            },
+            |(k, values)| {
+                let indexed: Vec<(usize, usize)> = values.iter().copied().enumerate().collect();
+                let mut sorted = indexed.clone();
+                sorted.sort_by_key(|(_, value)| Reverse(*value));
+                sorted.truncate(*k);
+
+                top_k_by_key(indexed, *k, |(_, value)| *value) == sorted
+            },
+        );
+    }
+}
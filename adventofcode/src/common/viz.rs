@@ -0,0 +1,564 @@
+//! Step-by-step terminal animations of solver state.
+//!
+//! Solvers push `Frame`s (grids of coloured characters) into an `Animation`,
+//! which is played back once solving is done, using the alternate screen so
+//! the normal output isn't lost. When stdin is a terminal, playback reacts to
+//! keys: space pauses, `n` steps one frame, `+`/`-` change speed, `q` quits.
+
+use std::{
+    fmt::Display,
+    fs::File,
+    io::{self, BufWriter, Read, Write},
+    thread,
+    time::Duration,
+};
+
+use super::{
+    config::Options,
+    term::{self, RawMode},
+};
+
+const DEFAULT_FPS: u32 = 30;
+const MAX_FPS: u32 = 1000;
+/// About 12 MB of 61x25 frames.
+const DEFAULT_MAX_FRAMES: usize = 1000;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Color {
+    Red,
+    Green,
+    Yellow,
+    Blue,
+    Magenta,
+    Cyan,
+    White,
+    Gray,
+}
+
+impl Color {
+    fn ansi_code(&self) -> u8 {
+        return match self {
+            Color::Red => 31,
+            Color::Green => 32,
+            Color::Yellow => 33,
+            Color::Blue => 34,
+            Color::Magenta => 35,
+            Color::Cyan => 36,
+            Color::White => 37,
+            Color::Gray => 90,
+        };
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Cell {
+    pub ch: char,
+    pub color: Option<Color>,
+}
+
+/// A grid of characters with an optional caption line below it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Frame {
+    width: usize,
+    height: usize,
+    cells: Vec<Cell>,
+    caption: String,
+}
+
+impl Frame {
+    pub fn new(width: usize, height: usize, background: char) -> Self {
+        return Self {
+            width,
+            height,
+            cells: vec![
+                Cell {
+                    ch: background,
+                    color: None,
+                };
+                width * height
+            ],
+            caption: String::new(),
+        };
+    }
+
+    pub fn width(&self) -> usize {
+        return self.width;
+    }
+
+    pub fn height(&self) -> usize {
+        return self.height;
+    }
+
+    /// Out of bounds positions are ignored, so callers can draw partially
+    /// visible shapes without clipping them first.
+    pub fn set(&mut self, x: usize, y: usize, ch: char, color: Option<Color>) {
+        if x < self.width && y < self.height {
+            self.cells[y * self.width + x] = Cell { ch, color };
+        }
+    }
+
+    pub fn get(&self, x: usize, y: usize) -> Option<&Cell> {
+        if x < self.width && y < self.height {
+            return Some(&self.cells[y * self.width + x]);
+        } else {
+            return None;
+        }
+    }
+
+    pub fn caption(&self) -> &str {
+        return &self.caption;
+    }
+
+    pub fn set_caption(&mut self, caption: impl Into<String>) {
+        self.caption = caption.into();
+    }
+
+    pub fn rows(&self) -> impl Iterator<Item = &[Cell]> {
+        return self.cells.chunks(self.width.max(1)).take(self.height);
+    }
+
+    /// The frame with ANSI colour escape codes, lines end with `\r\n` so it
+    /// renders the same whether the terminal is in raw mode or not.
+    pub fn to_ansi_string(&self) -> String {
+        let mut res = String::new();
+        for row in self.rows() {
+            let mut current_color: Option<Color> = None;
+            for cell in row {
+                if cell.color != current_color {
+                    match cell.color {
+                        Some(color) => res += &format!("\x1b[{}m", color.ansi_code()),
+                        None => res += "\x1b[0m",
+                    }
+                    current_color = cell.color;
+                }
+                res.push(cell.ch);
+            }
+            if current_color.is_some() {
+                res += "\x1b[0m";
+            }
+            res += "\r\n";
+        }
+        res += &self.caption;
+        return res;
+    }
+}
+
+/// Plain text, without colours.
+impl Display for Frame {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for row in self.rows() {
+            for cell in row {
+                write!(f, "{}", cell.ch)?;
+            }
+            writeln!(f)?;
+        }
+        return write!(f, "{}", self.caption);
+    }
+}
+
+/// Frames recorded by a solver, and how to show them.
+///
+/// Dumped frames are written as they are pushed. Only up to `max_frames` are
+/// kept for playback: when full, every other frame is dropped and from then on
+/// only every other pushed frame is kept, so long runs are played faster
+/// instead of filling the memory.
+#[derive(Debug)]
+pub struct Animation {
+    frames: Vec<Frame>,
+    fps: u32,
+    play: bool,
+    max_frames: usize,
+    /// Frames pushed so far.
+    pushed: usize,
+    /// One frame out of `stride` is kept for playback.
+    stride: usize,
+    /// The latest frame when it wasn't kept, so playback ends on it.
+    last: Option<Frame>,
+    dump: Option<Dump>,
+}
+
+/// The file frames are dumped to, and the first error writing it.
+#[derive(Debug)]
+struct Dump {
+    path: String,
+    writer: BufWriter<File>,
+    error: Option<io::Error>,
+}
+
+impl Animation {
+    pub fn new(fps: u32) -> Self {
+        return Self {
+            frames: Vec::new(),
+            fps: fps.clamp(1, MAX_FPS),
+            play: true,
+            max_frames: DEFAULT_MAX_FRAMES,
+            pushed: 0,
+            stride: 1,
+            last: None,
+            dump: None,
+        };
+    }
+
+    /// Reads `--animate`, `--fps=<n>`, `--max-frames=<n>` and
+    /// `--dump-frames=<path>`. Returns `None` when neither playing nor dumping
+    /// was asked for, so solvers can skip building frames altogether.
+    pub fn from_options(options: &Options) -> Result<Option<Self>, String> {
+        let fps = options.parse_value("fps")?.unwrap_or(DEFAULT_FPS);
+        let max_frames = options
+            .parse_value("max-frames")?
+            .unwrap_or(DEFAULT_MAX_FRAMES);
+        let dump_path = options.parse_value::<String>("dump-frames")?;
+        let play = options.has("animate");
+        if !play && dump_path.is_none() {
+            return Ok(None);
+        }
+
+        let mut animation = Self::new(fps);
+        animation.play = play;
+        animation.max_frames = max_frames.max(2);
+        if let Some(path) = dump_path {
+            let file = File::create(&path)
+                .map_err(|err| format!("could not write frames to {}: {}", path, err))?;
+            animation.dump = Some(Dump {
+                path,
+                writer: BufWriter::new(file),
+                error: None,
+            });
+        }
+        return Ok(Some(animation));
+    }
+
+    pub fn push(&mut self, frame: Frame) {
+        let index = self.pushed;
+        self.pushed += 1;
+        if let Some(dump) = &mut self.dump {
+            dump.write(index, &frame);
+        }
+        if !self.play {
+            return;
+        }
+
+        if index.is_multiple_of(self.stride) && self.frames.len() >= self.max_frames {
+            let mut kept = 0;
+            self.frames.retain(|_| {
+                kept += 1;
+                kept % 2 == 1
+            });
+            self.stride *= 2;
+        }
+        if index.is_multiple_of(self.stride) {
+            self.frames.push(frame);
+            self.last = None;
+        } else {
+            self.last = Some(frame);
+        }
+    }
+
+    /// The frames kept for playback.
+    pub fn frames(&self) -> &[Frame] {
+        return &self.frames;
+    }
+
+    pub fn len(&self) -> usize {
+        return self.frames.len();
+    }
+
+    pub fn is_empty(&self) -> bool {
+        return self.frames.is_empty();
+    }
+
+    /// Finishes the dump and/or plays the recorded frames, as configured.
+    pub fn finish(mut self) -> Result<(), String> {
+        if let Some(dump) = self.dump.take() {
+            let path = dump.path.clone();
+            dump.finish()
+                .map_err(|err| format!("could not write frames to {}: {}", path, err))?;
+        }
+        if let Some(last) = self.last.take() {
+            self.frames.push(last);
+        }
+        if self.play {
+            self.play()
+                .map_err(|err| format!("animation error: {}", err))?;
+        }
+        return Ok(());
+    }
+
+    pub fn play(&self) -> io::Result<()> {
+        if self.frames.is_empty() {
+            return Ok(());
+        }
+
+        let mut stdout = io::stdout().lock();
+        let raw_mode = if term::is_interactive() {
+            RawMode::non_blocking()
+        } else {
+            None
+        };
+
+        // alternate screen, hidden cursor
+        write!(stdout, "\x1b[?1049h\x1b[?25l")?;
+        let res = self.play_frames(&mut stdout, raw_mode.is_some());
+        write!(stdout, "\x1b[?25h\x1b[?1049l")?;
+        stdout.flush()?;
+
+        drop(raw_mode);
+        return res;
+    }
+
+    fn play_frames(&self, out: &mut impl Write, interactive: bool) -> io::Result<()> {
+        let mut state = Playback {
+            index: 0,
+            paused: false,
+            fps: self.fps,
+        };
+
+        loop {
+            let frame = &self.frames[state.index];
+            let status = format!(
+                "frame {}/{} at {} fps{}",
+                state.index + 1,
+                self.frames.len(),
+                state.fps,
+                if interactive {
+                    "  [space] pause  [n] step  [+/-] speed  [q] quit"
+                } else {
+                    ""
+                },
+            );
+            // cursor home, frame, clear to the end of the screen
+            write!(
+                out,
+                "\x1b[H{}\x1b[K\r\n\x1b[2m{}\x1b[0m\x1b[J",
+                frame.to_ansi_string(),
+                status
+            )?;
+            out.flush()?;
+
+            let is_last = state.index + 1 == self.frames.len();
+            if is_last && !interactive {
+                return Ok(());
+            }
+            if is_last {
+                state.paused = true;
+            }
+
+            let mut advance = false;
+            let deadline = Duration::from_secs_f64(1.0 / state.fps as f64);
+            let mut waited = Duration::ZERO;
+            loop {
+                if interactive {
+                    match state.handle_keys(read_keys()) {
+                        KeyAction::Quit => return Ok(()),
+                        KeyAction::Step => {
+                            advance = true;
+                            break;
+                        }
+                        KeyAction::Redraw => break,
+                        KeyAction::None => (),
+                    }
+                }
+                if !state.paused && waited >= deadline {
+                    advance = true;
+                    break;
+                }
+
+                let tick = if interactive {
+                    Duration::from_millis(10).min(deadline)
+                } else {
+                    deadline
+                };
+                thread::sleep(tick);
+                waited += tick;
+            }
+
+            if advance && !is_last {
+                state.index += 1;
+            }
+        }
+    }
+}
+
+impl Dump {
+    /// Writes a frame as plain text preceded by a header line. After an error
+    /// nothing more is written, the error is returned by `finish`.
+    fn write(&mut self, index: usize, frame: &Frame) {
+        if self.error.is_some() {
+            return;
+        }
+        let res = writeln!(self.writer, "--- frame {} ---", index + 1)
+            .and_then(|_| writeln!(self.writer, "{}", frame));
+        if let Err(err) = res {
+            self.error = Some(err);
+        }
+    }
+
+    fn finish(mut self) -> io::Result<()> {
+        if let Some(err) = self.error {
+            return Err(err);
+        }
+        return self.writer.flush();
+    }
+}
+
+struct Playback {
+    index: usize,
+    paused: bool,
+    fps: u32,
+}
+
+enum KeyAction {
+    None,
+    Redraw,
+    Step,
+    Quit,
+}
+
+impl Playback {
+    fn handle_keys(&mut self, keys: Vec<u8>) -> KeyAction {
+        let mut action = KeyAction::None;
+        for key in keys {
+            action = match key {
+                b'q' | 0x1b => return KeyAction::Quit,
+                b' ' => {
+                    self.paused = !self.paused;
+                    KeyAction::Redraw
+                }
+                b'n' | b'.' => {
+                    self.paused = true;
+                    KeyAction::Step
+                }
+                b'+' | b'=' => {
+                    self.fps = (self.fps * 2).min(MAX_FPS);
+                    KeyAction::Redraw
+                }
+                b'-' => {
+                    self.fps = (self.fps / 2).max(1);
+                    KeyAction::Redraw
+                }
+                _ => action,
+            };
+        }
+        return action;
+    }
+}
+
+/// Whatever was typed since the last call, without blocking.
+fn read_keys() -> Vec<u8> {
+    let mut buf = [0u8; 16];
+    return match io::stdin().read(&mut buf) {
+        Ok(size) => buf[..size].to_vec(),
+        Err(_) => Vec::new(),
+    };
+}
+
+#[cfg(test)]
+mod test {
+    use super::{Animation, Color, Frame, KeyAction, Playback};
+    use crate::common::config::Options;
+
+    #[test]
+    fn frame_rendering() {
+        let mut frame = Frame::new(3, 2, '.');
+        frame.set(1, 0, '#', Some(Color::Red));
+        frame.set(2, 1, 'x', None);
+        frame.set(5, 5, '!', None);
+        frame.set_caption("step 1");
+
+        assert_eq!(frame.to_string(), ".#.\n..x\nstep 1");
+        assert_eq!(
+            frame.to_ansi_string(),
+            ".\x1b[31m#\x1b[0m.\r\n..x\r\nstep 1"
+        );
+        assert_eq!(frame.get(1, 0).unwrap().color, Some(Color::Red));
+        assert_eq!(frame.get(3, 0), None);
+    }
+
+    #[test]
+    fn animation_options() {
+        let (options, _) = Options::parse(std::iter::empty());
+        assert!(Animation::from_options(&options).unwrap().is_none());
+
+        let path = std::env::temp_dir().join("adventofcode_viz_options_test.txt");
+        let path = path.to_str().unwrap();
+        let args = [format!("--dump-frames={}", path), "--fps=5".to_string()];
+        let (options, _) = Options::parse(args.into_iter());
+        let animation = Animation::from_options(&options).unwrap().unwrap();
+        let _ = std::fs::remove_file(path);
+        assert_eq!(animation.fps, 5);
+        assert!(!animation.play);
+        assert_eq!(animation.dump.unwrap().path, path);
+    }
+
+    #[test]
+    fn streamed_dump() {
+        let path = std::env::temp_dir().join("adventofcode_viz_dump_test.txt");
+        let path = path.to_str().unwrap();
+        let args = [format!("--dump-frames={}", path)];
+        let (options, _) = Options::parse(args.into_iter());
+        let mut animation = Animation::from_options(&options).unwrap().unwrap();
+
+        animation.push(Frame::new(1, 1, 'a'));
+        animation.push(Frame::new(1, 1, 'b'));
+        // only dumped, not kept
+        assert!(animation.is_empty());
+        animation.finish().unwrap();
+
+        let dump = std::fs::read_to_string(path).unwrap();
+        let _ = std::fs::remove_file(path);
+        assert_eq!(dump, "--- frame 1 ---\na\n\n--- frame 2 ---\nb\n\n");
+    }
+
+    #[test]
+    fn kept_frames_are_capped() {
+        let mut animation = Animation::new(30);
+        animation.max_frames = 4;
+
+        for index in 0..11 {
+            let mut frame = Frame::new(0, 0, ' ');
+            frame.set_caption(index.to_string());
+            animation.push(frame);
+        }
+
+        let captions: Vec<&str> = animation.frames().iter().map(|f| f.caption()).collect();
+        assert_eq!(captions, ["0", "4", "8"]);
+        assert_eq!(animation.stride, 4);
+        assert_eq!(animation.last.as_ref().unwrap().caption(), "10");
+    }
+
+    #[test]
+    fn playback_keys() {
+        let mut state = Playback {
+            index: 0,
+            paused: false,
+            fps: 10,
+        };
+
+        assert!(matches!(state.handle_keys(vec![b' ']), KeyAction::Redraw));
+        assert!(state.paused);
+        assert!(matches!(state.handle_keys(vec![b'n']), KeyAction::Step));
+        assert!(matches!(
+            state.handle_keys(vec![b'+', b'+']),
+            KeyAction::Redraw
+        ));
+        assert_eq!(state.fps, 40);
+        assert!(matches!(
+            state.handle_keys(vec![b'-', b'q']),
+            KeyAction::Quit
+        ));
+    }
+
+    #[test]
+    fn non_interactive_playback() {
+        let mut animation = Animation::new(1000);
+        animation.push(Frame::new(1, 1, 'a'));
+        animation.push(Frame::new(1, 1, 'b'));
+
+        let mut out: Vec<u8> = Vec::new();
+        animation.play_frames(&mut out, false).unwrap();
+
+        let out = String::from_utf8(out).unwrap();
+        assert!(out.contains("frame 1/2"));
+        assert!(out.contains("\x1b[Hb\r\n"));
+    }
+}
@@ -1,53 +1,254 @@
+use std::{fmt::Display, num::IntErrorKind};
+
+use crate::common::top_k::TopK;
+
 #[derive(Debug, PartialEq, PartialOrd, Eq, Ord)]
 pub struct Elf {
     pub calories: usize,
-    id: usize,
+    /// Position in the input, from 0.
+    pub id: usize,
+    /// Calories of each item, in input order.
+    pub items: Vec<usize>,
 }
 
 impl Elf {
-    pub fn new(id: usize, calories: usize) -> Self {
-        return Elf { calories, id };
+    pub fn new(id: usize, items: Vec<usize>) -> Self {
+        return Elf {
+            calories: items.iter().sum(),
+            id,
+            items,
+        };
     }
 }
 
-pub fn parse_elves<'a>(input: impl Iterator<Item = &'a str>) -> Vec<Elf> {
-    let mut elves: Vec<Elf> = Vec::new();
+/// Elves in input order, summed while the lines are read. Elves are separated
+/// by one or more blank lines, the last one doesn't need a blank line after it.
+pub struct Elves<I> {
+    lines: I,
+    line_number: usize,
+    next_id: usize,
+    failed: bool,
+}
 
-    let mut current_total = 0;
+impl<'a, I: Iterator<Item = &'a str>> Iterator for Elves<I> {
+    type Item = Result<Elf, ElfParseError>;
 
-    for line in input {
-        if line.is_empty() {
-            // exactly one empty line after each elf's data
-            elves.push(Elf::new(elves.len(), current_total));
-            current_total = 0;
-        } else {
-            current_total += line.parse::<usize>().expect("should parse int");
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.failed {
+            return None;
         }
+        let mut current_total: usize = 0;
+        let mut items = Vec::new();
+
+        for line in self.lines.by_ref() {
+            self.line_number += 1;
+            let text = line.trim();
+            if text.is_empty() {
+                if items.is_empty() {
+                    continue;
+                }
+                return Some(Ok(self.new_elf(items)));
+            }
+
+            let line_number = self.line_number;
+            let calories = text.parse::<usize>().map_err(|err| match err.kind() {
+                IntErrorKind::PosOverflow => ElfParseError::Overflow(line_number),
+                _ => ElfParseError::InvalidCalories(line_number, text.to_string()),
+            });
+            let total = calories.and_then(|calories| {
+                items.push(calories);
+                calories
+                    .checked_add(current_total)
+                    .ok_or(ElfParseError::Overflow(line_number))
+            });
+            match total {
+                Ok(total) => current_total = total,
+                Err(err) => {
+                    self.failed = true;
+                    return Some(Err(err));
+                }
+            }
+        }
+
+        if items.is_empty() {
+            return None;
+        }
+        return Some(Ok(self.new_elf(items)));
+    }
+}
+
+impl<I> Elves<I> {
+    /// `items` have been checked not to overflow.
+    fn new_elf(&mut self, items: Vec<usize>) -> Elf {
+        let elf = Elf::new(self.next_id, items);
+        self.next_id += 1;
+        return elf;
+    }
+}
+
+pub fn elves<'a, I: Iterator<Item = &'a str>>(input: I) -> Elves<I> {
+    return Elves {
+        lines: input,
+        line_number: 0,
+        next_id: 0,
+        failed: false,
+    };
+}
+
+/// The calories carried by all of `elves`, `None` if the sum overflows.
+pub fn total_calories<'a>(elves: impl IntoIterator<Item = &'a Elf>) -> Option<usize> {
+    return elves
+        .into_iter()
+        .try_fold(0usize, |total, elf| total.checked_add(elf.calories));
+}
+
+/// The `result_count` elves carrying the most calories, most first. Elves
+/// carrying the same amount keep their input order.
+pub fn get_highest_calorie_elves(
+    elves: impl IntoIterator<Item = Result<Elf, ElfParseError>>,
+    result_count: usize,
+) -> Result<Vec<Elf>, ElfParseError> {
+    let mut selector = TopK::new(result_count);
+    for elf in elves {
+        let elf = elf?;
+        selector.push(elf.calories, elf);
     }
+    return Ok(selector
+        .into_sorted_vec()
+        .into_iter()
+        .map(|(_, elf)| elf)
+        .collect());
+}
 
-    return elves;
+#[derive(Debug, PartialEq, Eq)]
+pub enum ElfParseError {
+    InvalidCalories(usize, String), // line number, text
+    Overflow(usize),                // line number
 }
 
-pub fn get_highest_calorie_elves(calories: &mut Vec<Elf>, result_count: usize) -> &[Elf] {
-    // TODO don't sort the whole list, try to get the `result_count` maximum values
-    calories.sort();
-    return &calories[calories.len() - result_count..];
+impl From<ElfParseError> for String {
+    fn from(value: ElfParseError) -> Self {
+        return value.to_string();
+    }
+}
+
+impl Display for ElfParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        return match self {
+            ElfParseError::InvalidCalories(line, text) => {
+                write!(f, "line {}: invalid calories \"{}\"", line, text)
+            }
+            ElfParseError::Overflow(line) => {
+                write!(f, "line {}: calories overflow", line)
+            }
+        };
+    }
 }
 
 #[cfg(test)]
 mod test {
-    use crate::day01::elf::{parse_elves, Elf};
+    use crate::day01::elf::{elves, get_highest_calorie_elves, total_calories, Elf, ElfParseError};
+
+    fn parse(input: &str) -> Result<Vec<Elf>, ElfParseError> {
+        return elves(input.lines()).collect();
+    }
 
     #[test]
     fn create_elf() {
-        let elf = Elf::new(0, 0);
+        let elf = Elf::new(0, vec![]);
         assert_eq!(elf.calories, 0);
     }
 
     #[test]
-    fn parse() {
-        let elves = parse_elves(["10", "123446", ""].into_iter());
+    fn parse_one_elf() {
+        let elves = parse("10\n123446\n\n").unwrap();
         assert_eq!(1, elves.len());
         assert_eq!(123456, elves[0].calories);
+        assert_eq!(vec![10, 123446], elves[0].items);
+    }
+
+    #[test]
+    fn last_elf_without_blank_line() {
+        assert_eq!(
+            parse("1\n2\n\n3").unwrap(),
+            vec![Elf::new(0, vec![1, 2]), Elf::new(1, vec![3])]
+        );
+    }
+
+    #[test]
+    fn blank_line_runs() {
+        let elves = parse("\n\n1\n\n\n\n2\r\n  \n3\n\n\n").unwrap();
+        assert_eq!(
+            elves,
+            vec![
+                Elf::new(0, vec![1]),
+                Elf::new(1, vec![2]),
+                Elf::new(2, vec![3])
+            ]
+        );
+        assert_eq!(parse("\n\n").unwrap(), vec![]);
+    }
+
+    #[test]
+    fn invalid_lines() {
+        assert_eq!(
+            parse("1\n\n2\nabc\n3\n"),
+            Err(ElfParseError::InvalidCalories(4, "abc".to_string()))
+        );
+        assert_eq!(
+            parse("-5").unwrap_err().to_string(),
+            "line 1: invalid calories \"-5\""
+        );
+    }
+
+    #[test]
+    fn overflow() {
+        let max = usize::MAX.to_string();
+        assert_eq!(parse(&format!("1\n\n{}\n", max)).unwrap().len(), 2);
+        assert_eq!(
+            parse(&format!("1\n{}\n", max)),
+            Err(ElfParseError::Overflow(2))
+        );
+        assert_eq!(
+            parse(&format!("{}0\n", max)),
+            Err(ElfParseError::Overflow(1))
+        );
+
+        let elves = parse(&format!("{}\n\n{}\n", max, max)).unwrap();
+        assert_eq!(total_calories(&elves), None);
+        assert_eq!(total_calories(&elves[..1]), Some(usize::MAX));
+    }
+
+    #[test]
+    fn highest_calorie_elves() {
+        let input = ["1000", "", "3000", "", "2000", "", "3000", "", "500", ""];
+
+        let top = get_highest_calorie_elves(elves(input.into_iter()), 3).unwrap();
+
+        assert_eq!(
+            top,
+            vec![
+                Elf::new(1, vec![3000]),
+                Elf::new(3, vec![3000]),
+                Elf::new(2, vec![2000])
+            ]
+        );
+    }
+
+    #[test]
+    fn more_requested_than_elves() {
+        let top = get_highest_calorie_elves(elves(["5", "", "7"].into_iter()), 3).unwrap();
+
+        assert_eq!(top, vec![Elf::new(1, vec![7]), Elf::new(0, vec![5])]);
+    }
+
+    #[test]
+    fn stops_at_first_error() {
+        let mut parsed = elves(["5", "x", "7"].into_iter());
+
+        assert!(matches!(parsed.next(), Some(Err(_))));
+        assert!(parsed.next().is_none());
+        let top = get_highest_calorie_elves(elves(["5", "", "x"].into_iter()), 1);
+        assert_eq!(top, Err(ElfParseError::InvalidCalories(3, "x".to_string())));
     }
 }
@@ -0,0 +1,34 @@
+//! Random day 1 inputs: blocks of calorie counts separated by blank lines.
+
+use crate::common::rng::Rng;
+
+/// `elf_count` elves carrying 1 to 15 items each.
+pub fn generate(rng: &mut Rng, elf_count: usize) -> String {
+    let mut out = String::new();
+    for _ in 0..elf_count {
+        for _ in 0..rng.between(1, 15) {
+            out += &rng.between(1000, 60_000).to_string();
+            out.push('\n');
+        }
+        // a blank line after every elf, the last one included, like real inputs
+        out.push('\n');
+    }
+    return out;
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        common::rng::Rng,
+        day01::elf::{elves, Elf},
+    };
+
+    #[test]
+    fn generated_input_parses() {
+        let input = super::generate(&mut Rng::new(1), 50);
+        let elves: Vec<Elf> = elves(input.lines()).collect::<Result<_, _>>().unwrap();
+
+        assert_eq!(elves.len(), 50);
+        assert!(elves.iter().all(|elf| elf.calories >= 1000));
+    }
+}
@@ -0,0 +1,255 @@
+//! Every elf with its items, and the queries of `--query`.
+
+use std::{collections::BTreeMap, fmt::Display};
+
+use crate::{
+    common::top_k::top_k_by_key,
+    day01::elf::{self, Elf, ElfParseError},
+};
+
+pub struct Inventory {
+    elves: Vec<Elf>,
+}
+
+impl Inventory {
+    pub fn parse(input: &str) -> Result<Self, ElfParseError> {
+        let elves = elf::elves(input.lines()).collect::<Result<_, _>>()?;
+        return Ok(Inventory { elves });
+    }
+
+    pub fn elves(&self) -> &[Elf] {
+        return &self.elves;
+    }
+
+    /// The elf carrying the item with the most calories, the first one on ties.
+    pub fn largest_item(&self) -> Option<(&Elf, usize)> {
+        let mut largest: Option<(&Elf, usize)> = None;
+        for elf in &self.elves {
+            for &item in &elf.items {
+                if largest.is_none_or(|(_, calories)| item > calories) {
+                    largest = Some((elf, item));
+                }
+            }
+        }
+        return largest;
+    }
+
+    /// Elves carrying more than `calories`, in input order.
+    pub fn above(&self, calories: usize) -> Vec<&Elf> {
+        return self
+            .elves
+            .iter()
+            .filter(|elf| elf.calories > calories)
+            .collect();
+    }
+
+    /// Number of elves for each number of items.
+    pub fn item_counts(&self) -> BTreeMap<usize, usize> {
+        let mut counts = BTreeMap::new();
+        for elf in &self.elves {
+            *counts.entry(elf.items.len()).or_default() += 1;
+        }
+        return counts;
+    }
+
+    pub fn top_by_count(&self, k: usize) -> Vec<&Elf> {
+        return top_k_by_key(&self.elves, k, |elf| elf.items.len());
+    }
+
+    pub fn top_by_total(&self, k: usize) -> Vec<&Elf> {
+        return top_k_by_key(&self.elves, k, |elf| elf.calories);
+    }
+
+    /// Runs each query of `queries`, separated by `;`.
+    pub fn query(&self, queries: &str) -> Result<String, QueryError> {
+        let mut out = String::new();
+        for query in queries.split(';').filter(|query| !query.trim().is_empty()) {
+            out += &self.run(&Query::parse(query)?);
+        }
+        return Ok(out);
+    }
+
+    fn run(&self, query: &Query) -> String {
+        let mut out = String::new();
+        match query {
+            Query::LargestItem => match self.largest_item() {
+                Some((elf, calories)) => {
+                    out += &format!(
+                        "largest item: {} calories, carried by elf {}\n",
+                        calories, elf.id
+                    );
+                }
+                None => out += "largest item: none\n",
+            },
+            Query::Above(calories) => {
+                let elves = self.above(*calories);
+                out += &format!(
+                    "{} elves carry more than {} calories\n",
+                    elves.len(),
+                    calories
+                );
+                out += &list(&elves);
+            }
+            Query::ItemCounts => {
+                out += "elves per number of items\n";
+                for (items, elves) in self.item_counts() {
+                    out += &format!("  {:>3} items: {}\n", items, elves);
+                }
+            }
+            Query::TopByCount(k) => {
+                out += &format!("top {} elves by number of items\n", k);
+                out += &list(&self.top_by_count(*k));
+            }
+            Query::TopByTotal(k) => {
+                out += &format!("top {} elves by calories\n", k);
+                out += &list(&self.top_by_total(*k));
+            }
+        }
+        return out;
+    }
+}
+
+fn list(elves: &[&Elf]) -> String {
+    return elves
+        .iter()
+        .map(|elf| {
+            format!(
+                "  elf {}: {} calories in {} items\n",
+                elf.id,
+                elf.calories,
+                elf.items.len()
+            )
+        })
+        .collect();
+}
+
+/// ```text
+/// largest-item
+/// above <calories>
+/// counts
+/// top <k> [by count|by total]
+/// ```
+#[derive(Debug, PartialEq, Eq)]
+pub enum Query {
+    LargestItem,
+    Above(usize),
+    ItemCounts,
+    TopByCount(usize),
+    TopByTotal(usize),
+}
+
+impl Query {
+    pub fn parse(text: &str) -> Result<Self, QueryError> {
+        let words: Vec<&str> = text.split_whitespace().collect();
+        return match words.as_slice() {
+            ["largest-item"] => Ok(Query::LargestItem),
+            ["above", calories] => Ok(Query::Above(parse_number(calories)?)),
+            ["counts"] => Ok(Query::ItemCounts),
+            ["top", k] | ["top", k, "by", "total"] => Ok(Query::TopByTotal(parse_number(k)?)),
+            ["top", k, "by", "count"] => Ok(Query::TopByCount(parse_number(k)?)),
+            _ => Err(QueryError::Unknown(text.trim().to_string())),
+        };
+    }
+}
+
+fn parse_number(text: &str) -> Result<usize, QueryError> {
+    return text
+        .parse()
+        .map_err(|_| QueryError::InvalidNumber(text.to_string()));
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum QueryError {
+    Unknown(String),
+    InvalidNumber(String),
+}
+
+impl From<QueryError> for String {
+    fn from(value: QueryError) -> Self {
+        return value.to_string();
+    }
+}
+
+impl Display for QueryError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        return match self {
+            QueryError::Unknown(query) => write!(
+                f,
+                "unknown query \"{}\", expected largest-item, above <calories>, counts \
+                 or top <k> [by count|by total]",
+                query
+            ),
+            QueryError::InvalidNumber(text) => write!(f, "invalid number \"{}\"", text),
+        };
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{Inventory, Query, QueryError};
+
+    const EXAMPLE_INPUT: &str = "\
+1000
+2000
+3000
+
+4000
+
+5000
+6000
+
+7000
+8000
+9000
+
+10000
+";
+
+    #[test]
+    fn queries() {
+        let inventory = Inventory::parse(EXAMPLE_INPUT).unwrap();
+
+        let (elf, calories) = inventory.largest_item().unwrap();
+        assert_eq!((elf.id, calories), (4, 10000));
+        let above: Vec<usize> = inventory.above(10000).iter().map(|elf| elf.id).collect();
+        assert_eq!(above, vec![2, 3]);
+        assert_eq!(
+            inventory.item_counts().into_iter().collect::<Vec<_>>(),
+            vec![(1, 2), (2, 1), (3, 2)]
+        );
+        let by_count: Vec<usize> = inventory.top_by_count(2).iter().map(|elf| elf.id).collect();
+        assert_eq!(by_count, vec![0, 3]);
+        let by_total: Vec<usize> = inventory.top_by_total(2).iter().map(|elf| elf.id).collect();
+        assert_eq!(by_total, vec![3, 2]);
+    }
+
+    #[test]
+    fn parse_queries() {
+        assert_eq!(Query::parse(" largest-item "), Ok(Query::LargestItem));
+        assert_eq!(Query::parse("above 500"), Ok(Query::Above(500)));
+        assert_eq!(Query::parse("top 3"), Ok(Query::TopByTotal(3)));
+        assert_eq!(Query::parse("top 3 by count"), Ok(Query::TopByCount(3)));
+        assert_eq!(
+            Query::parse("top three"),
+            Err(QueryError::InvalidNumber("three".to_string()))
+        );
+        assert_eq!(
+            Query::parse("bottom 3"),
+            Err(QueryError::Unknown("bottom 3".to_string()))
+        );
+    }
+
+    #[test]
+    fn query_output() {
+        let inventory = Inventory::parse(EXAMPLE_INPUT).unwrap();
+
+        let out = inventory.query("largest-item; top 1 by count").unwrap();
+
+        assert_eq!(
+            out,
+            "largest item: 10000 calories, carried by elf 4\n\
+             top 1 elves by number of items\n  elf 0: 6000 calories in 3 items\n"
+        );
+        assert!(inventory.query("counts;;nope").is_err());
+    }
+}
@@ -1,26 +1,52 @@
 mod elf;
+pub mod generator;
+mod inventory;
+mod redistribute;
+mod stats;
 
-use crate::common::config::FilePathConfig;
-use std::fs;
+use crate::common::config::FileContentConfig;
 
-/// Solve day 1 https://adventofcode.com/2022/day/1
-pub fn solve(config: FilePathConfig) -> Result<(), &'static str> {
-    println!("Hello from day01! {:?}", config);
+pub const SOLVER_VERSION: &str = "2";
+/// Options printing more than the answers.
+pub const REPORT_OPTIONS: &[&str] = &["stats", "redistribute", "query"];
 
-    let contents = fs::read_to_string(&config.file_path);
-    if let Err(_) = contents {
-        return Err("could not open and read file {}");
+/// Part 1 is the most calories carried by one elf, part 2 by the top three.
+pub fn answer(part: u8, input: &str) -> Result<String, String> {
+    let count = if part == 1 { 1 } else { 3 };
+    let top_elves = elf::get_highest_calorie_elves(elf::elves(input.lines()), count)?;
+    if top_elves.len() < count {
+        return Err(format!("need at least {} elves", count));
     }
+    let total = elf::total_calories(&top_elves)
+        .ok_or_else(|| format!("the top {} elves carry too many calories to add up", count))?;
+    return Ok(total.to_string());
+}
+
+/// Solve day 1 https://adventofcode.com/2022/day/1
+pub fn solve(config: FileContentConfig) -> Result<(), String> {
+    println!("Hello from day01!");
 
-    let mut elves = elf::parse_elves(contents.unwrap().lines());
-    let count = 3;
-    let top_elves = elf::get_highest_calorie_elves(&mut elves, count);
-    dbg!(top_elves);
-    let mut total = 0;
-    for e in top_elves {
-        total += e.calories;
+    println!("Part 1 : most calories = {}", answer(1, &config.content)?);
+    println!("Part 2 : top three calories = {}", answer(2, &config.content)?);
+
+    let options = &config.options;
+    if !REPORT_OPTIONS.iter().any(|name| options.has(name)) {
+        return Ok(());
+    }
+    let inventory = inventory::Inventory::parse(&config.content)?;
+    if options.has("stats") {
+        let bin_count = options.parse_value("bins")?.unwrap_or(10);
+        match stats::Stats::new(inventory.elves(), bin_count) {
+            Some(stats) => print!("{}", stats),
+            None => println!("no elves"),
+        }
+    }
+    if options.has("redistribute") {
+        print!("{}", redistribute::plan(inventory.elves())?);
+    }
+    if let Some(queries) = options.parse_value::<String>("query")? {
+        print!("{}", inventory.query(&queries)?);
     }
-    dbg!(total);
 
     return Ok(());
 }
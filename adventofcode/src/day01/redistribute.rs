@@ -0,0 +1,576 @@
+//! Moves items between elves so the most loaded elf carries as few calories as
+//! possible: multiway number partitioning, solved exactly by branch and bound
+//! for small inputs and by LPT plus local search otherwise.
+
+use std::{
+    cmp::Reverse,
+    collections::{BTreeSet, BinaryHeap, HashMap},
+    fmt::Display,
+};
+
+use crate::day01::elf::{total_calories, Elf};
+
+/// Inputs with more items are partitioned by the heuristic.
+pub const EXACT_MAX_ITEMS: usize = 16;
+/// How many of the lightest bins the local search tries to move items to, in
+/// turn, before trying all of them.
+const LIGHTEST_BINS: [usize; 3] = [16, 256, usize::MAX];
+/// Item and bin pairs the local search may try, a fraction of a second.
+const SEARCH_BUDGET: usize = 20_000_000;
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct Transfer {
+    pub calories: usize,
+    /// Elf ids.
+    pub from: usize,
+    pub to: usize,
+}
+
+#[derive(Debug)]
+pub struct Plan {
+    /// Calories carried by each elf afterwards, in the order of the elves.
+    pub loads: Vec<usize>,
+    pub max_load: usize,
+    pub max_load_before: usize,
+    /// No partition can have a lower maximum.
+    pub lower_bound: usize,
+    pub exact: bool,
+    pub transfers: Vec<Transfer>,
+}
+
+/// Fails when the calories of all the elves don't fit in a `usize`, the loads
+/// of the bins can't overflow otherwise.
+pub fn plan(elves: &[Elf]) -> Result<Plan, String> {
+    let total = total_calories(elves)
+        .ok_or_else(|| "the elves carry too many calories to redistribute".to_string())?;
+    let mut items: Vec<usize> = elves
+        .iter()
+        .flat_map(|elf| elf.items.iter().copied())
+        .collect();
+    items.sort_unstable_by(|a, b| b.cmp(a));
+
+    let bin_count = elves.len();
+    let lower_bound = match bin_count {
+        0 => 0,
+        _ => total
+            .div_ceil(bin_count)
+            .max(items.first().copied().unwrap_or(0)),
+    };
+
+    let mut bins = longest_processing_time(&items, bin_count);
+    improve(&mut bins, lower_bound);
+    let exact = items.len() <= EXACT_MAX_ITEMS;
+    if exact {
+        bins = branch_and_bound(&items, bins, lower_bound);
+    }
+
+    // rebalancing what the elves carry usually reaches the same maximum with
+    // far fewer moves than handing out a new partition
+    let best_max = max_load(&bins);
+    let mut carried: Vec<Vec<usize>> = elves.iter().map(|elf| elf.items.clone()).collect();
+    improve(&mut carried, best_max);
+    if max_load(&carried) <= best_max {
+        bins = carried;
+    }
+
+    let owners = assign_bins(elves, bins);
+    let loads: Vec<usize> = owners.iter().map(|bin| bin.iter().sum()).collect();
+    return Ok(Plan {
+        max_load: loads.iter().copied().max().unwrap_or(0),
+        max_load_before: elves.iter().map(|elf| elf.calories).max().unwrap_or(0),
+        lower_bound,
+        exact,
+        transfers: transfers(elves, &owners),
+        loads,
+    });
+}
+
+/// Largest items first, each to the least loaded bin.
+fn longest_processing_time(items_descending: &[usize], bin_count: usize) -> Vec<Vec<usize>> {
+    let mut bins: Vec<Vec<usize>> = vec![Vec::new(); bin_count];
+    // (load, bin), the first of the lightest bins on top
+    let mut lightest: BinaryHeap<Reverse<(usize, usize)>> =
+        (0..bin_count).map(|bin| Reverse((0, bin))).collect();
+    for &item in items_descending {
+        let Some(Reverse((load, bin))) = lightest.pop() else {
+            break;
+        };
+        bins[bin].push(item);
+        lightest.push(Reverse((load + item, bin)));
+    }
+    return bins;
+}
+
+fn max_load(bins: &[Vec<usize>]) -> usize {
+    return bins.iter().map(|bin| bin.iter().sum()).max().unwrap_or(0);
+}
+
+/// Moves an item out of the heaviest bin, or swaps it for a smaller one, while
+/// that lowers the heaviest bin without making the other one as heavy, until
+/// no bin is heavier than `target` or `SEARCH_BUDGET` is spent.
+fn improve(bins: &mut [Vec<usize>], target: usize) {
+    let mut loads: Vec<usize> = bins.iter().map(|bin| bin.iter().sum()).collect();
+    // (load, bin), to find the heaviest and the lightest bins without a scan
+    let mut by_load: BTreeSet<(usize, usize)> = loads.iter().copied().zip(0..).collect();
+    for bin in bins.iter_mut() {
+        bin.sort_unstable();
+    }
+
+    // each step lowers the sum of the squared loads, so this ends, but can
+    // take long on large inputs
+    let mut budget = SEARCH_BUDGET;
+    loop {
+        let Some(&(top, heaviest)) = by_load.last() else {
+            return;
+        };
+        if top <= target {
+            return;
+        }
+
+        // the lightest bins usually have the best move, more are only tried
+        // when they don't
+        let others = by_load
+            .iter()
+            .map(|&(_, bin)| bin)
+            .filter(|&bin| bin != heaviest);
+        let mut best = None;
+        for count in LIGHTEST_BINS {
+            let tried = bins[heaviest].len() * count.min(bins.len() - 1);
+            if tried > budget {
+                return;
+            }
+            budget -= tried;
+            best = best_move(bins, &loads, heaviest, others.clone().take(count));
+            if best.is_some() || count >= bins.len() - 1 {
+                break;
+            }
+        }
+        let Some((other, i, swap)) = best else {
+            return;
+        };
+
+        by_load.remove(&(loads[heaviest], heaviest));
+        by_load.remove(&(loads[other], other));
+        let item = bins[heaviest].remove(i);
+        loads[heaviest] -= item;
+        if let Some(j) = swap {
+            let other_item = bins[other].remove(j);
+            loads[other] -= other_item;
+            insert_sorted(&mut bins[heaviest], other_item);
+            loads[heaviest] += other_item;
+        }
+        insert_sorted(&mut bins[other], item);
+        loads[other] += item;
+        by_load.insert((loads[heaviest], heaviest));
+        by_load.insert((loads[other], other));
+    }
+}
+
+/// The item of `heaviest` to move to one of `others`, and the item to swap it
+/// for if any, that leaves the larger of the two bins lightest:
+/// (other bin, item index, other item index).
+fn best_move(
+    bins: &[Vec<usize>],
+    loads: &[usize],
+    heaviest: usize,
+    others: impl Iterator<Item = usize> + Clone,
+) -> Option<(usize, usize, Option<usize>)> {
+    let top = loads[heaviest];
+    // (resulting larger load of the pair, bin, item index, other item index)
+    let mut best: Option<(usize, usize, usize, Option<usize>)> = None;
+    for (i, &item) in bins[heaviest].iter().enumerate() {
+        for other in others.clone() {
+            let gap = top - loads[other];
+            let mut consider = |delta: usize, swap: Option<usize>| {
+                if delta > 0 && delta < gap {
+                    let larger = (top - delta).max(loads[other] + delta);
+                    if best.is_none_or(|(current, ..)| larger < current) {
+                        best = Some((larger, other, i, swap));
+                    }
+                }
+            };
+            consider(item, None);
+            // the best swap moves about half the gap, look around it
+            let ideal = bins[other].partition_point(|&x| x + gap / 2 < item);
+            let around = bins[other].iter().enumerate().skip(ideal.saturating_sub(1));
+            for (j, &other_item) in around.take(2) {
+                if other_item < item {
+                    consider(item - other_item, Some(j));
+                }
+            }
+        }
+    }
+    return best.map(|(_, other, i, swap)| (other, i, swap));
+}
+
+fn insert_sorted(bin: &mut Vec<usize>, item: usize) {
+    let index = bin.partition_point(|&x| x < item);
+    bin.insert(index, item);
+}
+
+struct Search<'a> {
+    items: &'a [usize],
+    loads: Vec<usize>,
+    bins: Vec<Vec<usize>>,
+    best_max: usize,
+    best: Vec<Vec<usize>>,
+    lower_bound: usize,
+}
+
+/// Optimal partition, starting from a known one to prune with.
+fn branch_and_bound(
+    items_descending: &[usize],
+    known: Vec<Vec<usize>>,
+    lower_bound: usize,
+) -> Vec<Vec<usize>> {
+    let bin_count = known.len();
+    let mut search = Search {
+        items: items_descending,
+        loads: vec![0; bin_count],
+        bins: vec![Vec::new(); bin_count],
+        best_max: max_load(&known),
+        best: known,
+        lower_bound,
+    };
+    search.branch(0);
+    return search.best;
+}
+
+impl Search<'_> {
+    fn branch(&mut self, next: usize) {
+        if self.best_max <= self.lower_bound {
+            return;
+        }
+        let Some(&item) = self.items.get(next) else {
+            let max = self.loads.iter().copied().max().unwrap_or(0);
+            if max < self.best_max {
+                self.best_max = max;
+                self.best = self.bins.clone();
+            }
+            return;
+        };
+
+        let mut tried: Vec<usize> = Vec::new();
+        for bin in 0..self.loads.len() {
+            let load = self.loads[bin];
+            // bins with the same load, empty ones included, lead to the same
+            // partitions
+            if load + item >= self.best_max || tried.contains(&load) {
+                continue;
+            }
+            tried.push(load);
+
+            self.loads[bin] += item;
+            self.bins[bin].push(item);
+            self.branch(next + 1);
+            self.bins[bin].pop();
+            self.loads[bin] -= item;
+        }
+    }
+}
+
+/// Gives each bin to an elf, the elves already holding most of its items
+/// first, so that few items have to move.
+fn assign_bins(elves: &[Elf], bins: Vec<Vec<usize>>) -> Vec<Vec<usize>> {
+    // calories -> (elf, number of such items)
+    let mut holders: HashMap<usize, Vec<(usize, usize)>> = HashMap::new();
+    for (e, elf) in elves.iter().enumerate() {
+        for (calories, count) in counts(&elf.items) {
+            holders.entry(calories).or_default().push((e, count));
+        }
+    }
+
+    // (items in common, bin, elf)
+    let mut pairs: Vec<(usize, usize, usize)> = Vec::new();
+    for (b, bin) in bins.iter().enumerate() {
+        let mut shared: HashMap<usize, usize> = HashMap::new();
+        for (calories, count) in counts(bin) {
+            for &(e, held) in holders.get(&calories).into_iter().flatten() {
+                *shared.entry(e).or_default() += count.min(held);
+            }
+        }
+        pairs.extend(shared.into_iter().map(|(e, shared)| (shared, b, e)));
+    }
+    pairs.sort_unstable_by_key(|&(shared, b, e)| (Reverse(shared), b, e));
+
+    let mut owners: Vec<Option<Vec<usize>>> = vec![None; elves.len()];
+    let mut bins: Vec<Option<Vec<usize>>> = bins.into_iter().map(Some).collect();
+    for (_, b, e) in pairs {
+        if owners[e].is_none() && bins[b].is_some() {
+            owners[e] = bins[b].take();
+        }
+    }
+    let mut remaining = bins.into_iter().flatten();
+    return owners
+        .into_iter()
+        .map(|owner| owner.or_else(|| remaining.next()).unwrap_or_default())
+        .collect();
+}
+
+/// Items that have to change hands to go from the elves' items to `owners`.
+fn transfers(elves: &[Elf], owners: &[Vec<usize>]) -> Vec<Transfer> {
+    let mut surplus: Vec<(usize, usize)> = Vec::new(); // calories, elf id
+    let mut needed: Vec<(usize, usize)> = Vec::new();
+    for (elf, owned) in elves.iter().zip(owners) {
+        let (given, received) = difference(&sorted(&elf.items), owned);
+        surplus.extend(given.into_iter().map(|calories| (calories, elf.id)));
+        needed.extend(received.into_iter().map(|calories| (calories, elf.id)));
+    }
+    surplus.sort_unstable();
+    needed.sort_unstable();
+
+    let mut transfers: Vec<Transfer> = surplus
+        .into_iter()
+        .zip(needed)
+        .map(|((calories, from), (_, to))| Transfer { calories, from, to })
+        .collect();
+    transfers.sort_by(|a, b| (a.from, b.calories).cmp(&(b.from, a.calories)));
+    return transfers;
+}
+
+fn sorted(items: &[usize]) -> Vec<usize> {
+    let mut items = items.to_vec();
+    items.sort_unstable();
+    return items;
+}
+
+/// How many items of each calories.
+fn counts(items: &[usize]) -> HashMap<usize, usize> {
+    let mut counts = HashMap::new();
+    for &item in items {
+        *counts.entry(item).or_default() += 1;
+    }
+    return counts;
+}
+
+/// Items only in `a` and only in `b`, both sorted.
+fn difference(a: &[usize], b: &[usize]) -> (Vec<usize>, Vec<usize>) {
+    let (mut only_a, mut only_b) = (Vec::new(), Vec::new());
+    let (mut i, mut j) = (0, 0);
+    while i < a.len() || j < b.len() {
+        match (a.get(i), b.get(j)) {
+            (Some(x), Some(y)) if x == y => {
+                i += 1;
+                j += 1;
+            }
+            (Some(x), Some(y)) if x < y => {
+                only_a.push(*x);
+                i += 1;
+            }
+            (Some(x), None) => {
+                only_a.push(*x);
+                i += 1;
+            }
+            (_, Some(y)) => {
+                only_b.push(*y);
+                j += 1;
+            }
+            (None, None) => unreachable!(),
+        }
+    }
+    return (only_a, only_b);
+}
+
+impl Plan {
+    /// Solved exactly, or down to the lower bound anyway.
+    pub fn is_optimal(&self) -> bool {
+        return self.exact || self.max_load == self.lower_bound;
+    }
+}
+
+impl Display for Plan {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(
+            f,
+            "most calories carried by one elf: {} before, {} after ({}, lower bound {})",
+            self.max_load_before,
+            self.max_load,
+            if self.is_optimal() {
+                "optimal"
+            } else {
+                "heuristic"
+            },
+            self.lower_bound
+        )?;
+        writeln!(
+            f,
+            "calories per elf after the moves: {} to {}",
+            self.loads.iter().min().unwrap_or(&0),
+            self.max_load
+        )?;
+        writeln!(f, "items to move: {}", self.transfers.len())?;
+        for transfer in &self.transfers {
+            writeln!(
+                f,
+                "  {} calories from elf {} to elf {}",
+                transfer.calories, transfer.from, transfer.to
+            )?;
+        }
+        return Ok(());
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{plan, Transfer};
+    use crate::{
+        common::{prop, rng::Rng},
+        day01::{elf::Elf, generator},
+    };
+
+    fn elves(items: &[&[usize]]) -> Vec<Elf> {
+        return items
+            .iter()
+            .enumerate()
+            .map(|(id, items)| Elf::new(id, items.to_vec()))
+            .collect();
+    }
+
+    /// Applies the transfers and returns the calories of each elf.
+    fn apply(elves: &[Elf], transfers: &[Transfer]) -> Vec<usize> {
+        let mut items: Vec<Vec<usize>> = elves.iter().map(|elf| elf.items.clone()).collect();
+        for transfer in transfers {
+            let from = &mut items[transfer.from];
+            let index = from.iter().position(|&c| c == transfer.calories).unwrap();
+            from.swap_remove(index);
+            items[transfer.to].push(transfer.calories);
+        }
+        return items.iter().map(|items| items.iter().sum()).collect();
+    }
+
+    #[test]
+    fn example() {
+        let elves = elves(&[
+            &[1000, 2000, 3000],
+            &[4000],
+            &[5000, 6000],
+            &[7000, 8000, 9000],
+            &[10000],
+        ]);
+
+        let plan = plan(&elves).unwrap();
+
+        assert!(plan.exact);
+        assert_eq!(plan.max_load_before, 24000);
+        assert_eq!(plan.lower_bound, 11000);
+        assert_eq!(plan.max_load, 11000);
+        assert_eq!(apply(&elves, &plan.transfers), plan.loads);
+    }
+
+    /// Smallest maximum over every way to put the items in `bin_count` bins.
+    fn brute_force(items: &[usize], bin_count: usize) -> usize {
+        let mut best = usize::MAX;
+        for mut choice in 0..bin_count.pow(items.len() as u32) {
+            let mut loads = vec![0; bin_count];
+            for item in items {
+                loads[choice % bin_count] += item;
+                choice /= bin_count;
+            }
+            best = best.min(loads.into_iter().max().unwrap());
+        }
+        return best;
+    }
+
+    #[test]
+    fn greedy_is_not_enough() {
+        // largest first alone gives {3, 2, 2} {3, 2}
+        let elves = elves(&[&[3, 3, 2, 2, 2], &[]]);
+
+        let plan = plan(&elves).unwrap();
+
+        assert_eq!(plan.max_load, 6);
+        // the elf keeps the three items of 2 calories rather than the two of 3
+        let moved = || Transfer {
+            calories: 3,
+            from: 0,
+            to: 1,
+        };
+        assert_eq!(plan.transfers, vec![moved(), moved()]);
+    }
+
+    #[test]
+    fn optimal_for_small_inputs() {
+        prop::check(
+            |source| {
+                source.vec(1, 3, |source| {
+                    source.vec(0, 3, |source| source.between(1, 30))
+                })
+            },
+            |items| {
+                let items: Vec<&[usize]> = items.iter().map(|items| items.as_slice()).collect();
+                let elves = elves(&items);
+                let all: Vec<usize> = items.concat();
+
+                plan(&elves).unwrap().max_load == brute_force(&all, elves.len())
+            },
+        );
+    }
+
+    #[test]
+    fn balanced_input_moves_nothing() {
+        let elves = elves(&[&[3, 4], &[7], &[5, 2]]);
+
+        let plan = plan(&elves).unwrap();
+
+        assert_eq!(plan.max_load, 7);
+        assert!(plan.transfers.is_empty());
+    }
+
+    #[test]
+    fn heuristic_down_to_the_lower_bound() {
+        let elves = elves(&[&[100], &[1; 16]]);
+
+        let plan = plan(&elves).unwrap();
+
+        assert!(!plan.exact);
+        assert_eq!(plan.max_load, plan.lower_bound);
+        assert!(plan.to_string().contains("(optimal, lower bound 100)"));
+    }
+
+    #[test]
+    fn overflowing_total() {
+        let elves = elves(&[&[usize::MAX], &[1]]);
+
+        assert_eq!(
+            plan(&elves).err(),
+            Some("the elves carry too many calories to redistribute".to_string())
+        );
+    }
+
+    #[test]
+    fn heuristic_on_generated_input() {
+        let input = generator::generate(&mut Rng::new(4), 100);
+        let elves: Vec<Elf> = crate::day01::elf::elves(input.lines())
+            .collect::<Result<_, _>>()
+            .unwrap();
+
+        let plan = plan(&elves).unwrap();
+
+        assert!(!plan.exact);
+        assert!(plan.max_load >= plan.lower_bound);
+        assert!(plan.max_load < plan.max_load_before);
+        assert_eq!(apply(&elves, &plan.transfers), plan.loads);
+    }
+
+    #[test]
+    fn transfers_lead_to_the_loads() {
+        prop::check(
+            |source| {
+                source.vec(1, 6, |source| {
+                    source.vec(0, 4, |source| source.between(1, 20))
+                })
+            },
+            |items| {
+                let items: Vec<&[usize]> = items.iter().map(|items| items.as_slice()).collect();
+                let elves = elves(&items);
+                let plan = plan(&elves).unwrap();
+                let total: usize = elves.iter().map(|elf| elf.calories).sum();
+
+                apply(&elves, &plan.transfers) == plan.loads
+                    && plan.loads.iter().sum::<usize>() == total
+                    && plan.max_load >= plan.lower_bound
+                    && plan.max_load <= plan.max_load_before
+            },
+        );
+    }
+}
@@ -0,0 +1,267 @@
+//! Calorie statistics over all the elves, for `--stats`.
+
+use std::fmt::{Display, Write};
+
+use crate::day01::elf::Elf;
+
+pub const PERCENTILES: [u8; 5] = [10, 25, 75, 90, 99];
+const BAR_WIDTH: usize = 40;
+
+/// Summary of one value measured on every elf.
+#[derive(Debug, PartialEq)]
+pub struct Summary {
+    pub min: usize,
+    pub max: usize,
+    pub mean: f64,
+    pub median: f64,
+    /// Population standard deviation.
+    pub std_dev: f64,
+    /// Nearest-rank percentiles, for each of `PERCENTILES`.
+    pub percentiles: Vec<(u8, usize)>,
+}
+
+impl Summary {
+    /// `None` without values.
+    pub fn new(values: &[usize]) -> Option<Self> {
+        if values.is_empty() {
+            return None;
+        }
+        let mut sorted = values.to_vec();
+        sorted.sort_unstable();
+
+        let count = sorted.len() as f64;
+        let mean = sorted.iter().map(|&value| value as f64).sum::<f64>() / count;
+        let variance = sorted
+            .iter()
+            .map(|&value| (value as f64 - mean).powi(2))
+            .sum::<f64>()
+            / count;
+        let middle = sorted.len() / 2;
+        let median = if sorted.len().is_multiple_of(2) {
+            (sorted[middle - 1] as f64 + sorted[middle] as f64) / 2.0
+        } else {
+            sorted[middle] as f64
+        };
+
+        return Some(Summary {
+            min: sorted[0],
+            max: sorted[sorted.len() - 1],
+            mean,
+            median,
+            std_dev: variance.sqrt(),
+            percentiles: PERCENTILES
+                .iter()
+                .map(|&p| (p, percentile(&sorted, p)))
+                .collect(),
+        });
+    }
+}
+
+/// Smallest value with at least `p` percent of the values lower or equal.
+fn percentile(sorted: &[usize], p: u8) -> usize {
+    let rank = (p as usize * sorted.len()).div_ceil(100).max(1);
+    return sorted[rank - 1];
+}
+
+pub struct Stats<'a> {
+    pub elf_count: usize,
+    pub calories: Summary,
+    pub items: Summary,
+    pub least: &'a Elf,
+    pub most: &'a Elf,
+    pub histogram: Vec<Bin>,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct Bin {
+    /// Inclusive bounds.
+    pub start: usize,
+    pub end: usize,
+    pub count: usize,
+}
+
+impl<'a> Stats<'a> {
+    /// `None` without elves.
+    pub fn new(elves: &'a [Elf], bin_count: usize) -> Option<Self> {
+        let calories: Vec<usize> = elves.iter().map(|elf| elf.calories).collect();
+        let items: Vec<usize> = elves.iter().map(|elf| elf.items.len()).collect();
+
+        // first elf in input order on ties
+        let least = elves.iter().min_by_key(|elf| elf.calories)?;
+        let most = elves.iter().rev().max_by_key(|elf| elf.calories)?;
+
+        return Some(Stats {
+            elf_count: elves.len(),
+            calories: Summary::new(&calories)?,
+            items: Summary::new(&items)?,
+            least,
+            most,
+            histogram: histogram(&calories, bin_count),
+        });
+    }
+}
+
+/// `bin_count` bins of equal width covering every value.
+pub fn histogram(values: &[usize], bin_count: usize) -> Vec<Bin> {
+    let (Some(&min), Some(&max)) = (values.iter().min(), values.iter().max()) else {
+        return Vec::new();
+    };
+    // in u128 where the values span the whole range of `usize`
+    let span = (max - min) as u128;
+    let bin_count = (bin_count as u128).clamp(1, span + 1);
+    let width = span / bin_count + 1;
+
+    let mut bins: Vec<Bin> = (0..bin_count)
+        .map(|i| min as u128 + i * width)
+        // the last bins can be left out of range when the width was rounded up
+        .take_while(|&start| start <= max as u128)
+        .map(|start| Bin {
+            start: start as usize,
+            end: (start + width - 1).min(max as u128) as usize,
+            count: 0,
+        })
+        .collect();
+    for &value in values {
+        bins[((value - min) as u128 / width) as usize].count += 1;
+    }
+    return bins;
+}
+
+impl Display for Stats<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "{} elves", self.elf_count)?;
+        writeln!(
+            f,
+            "calories: least {} (elf {}), most {} (elf {})",
+            self.least.calories, self.least.id, self.most.calories, self.most.id
+        )?;
+        writeln!(f, "  {}", self.calories)?;
+        writeln!(f, "items per elf:")?;
+        writeln!(f, "  {}", self.items)?;
+        writeln!(f, "calories histogram:")?;
+
+        let start_width = self.histogram.iter().map(|bin| digits(bin.start)).max();
+        let end_width = self.histogram.iter().map(|bin| digits(bin.end)).max();
+        let largest = self
+            .histogram
+            .iter()
+            .map(|bin| bin.count)
+            .max()
+            .unwrap_or(0);
+        for bin in &self.histogram {
+            // at least one mark for a bin that isn't empty
+            let bar = "#".repeat((bin.count * BAR_WIDTH).div_ceil(largest.max(1)));
+            writeln!(
+                f,
+                "  {:>sw$} - {:>ew$} | {:<bw$} {}",
+                bin.start,
+                bin.end,
+                bar,
+                bin.count,
+                sw = start_width.unwrap_or(0),
+                ew = end_width.unwrap_or(0),
+                bw = BAR_WIDTH
+            )?;
+        }
+        return Ok(());
+    }
+}
+
+impl Display for Summary {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mut percentiles = String::new();
+        for (p, value) in &self.percentiles {
+            write!(percentiles, ", p{} {}", p, value)?;
+        }
+        return write!(
+            f,
+            "min {}, max {}, mean {:.1}, median {:.1}, std dev {:.1}{}",
+            self.min, self.max, self.mean, self.median, self.std_dev, percentiles
+        );
+    }
+}
+
+fn digits(value: usize) -> usize {
+    return value.to_string().len();
+}
+
+#[cfg(test)]
+mod test {
+    use super::{histogram, Bin, Stats, Summary};
+    use crate::day01::elf::Elf;
+
+    #[test]
+    fn summary() {
+        let summary = Summary::new(&[2, 4, 4, 4, 5, 5, 7, 9]).unwrap();
+
+        assert_eq!(summary.min, 2);
+        assert_eq!(summary.max, 9);
+        assert_eq!(summary.mean, 5.0);
+        assert_eq!(summary.median, 4.5);
+        assert_eq!(summary.std_dev, 2.0);
+        assert_eq!(
+            summary.percentiles,
+            vec![(10, 2), (25, 4), (75, 5), (90, 9), (99, 9)]
+        );
+        assert_eq!(Summary::new(&[3]).unwrap().median, 3.0);
+        assert_eq!(Summary::new(&[]), None);
+    }
+
+    fn bin(start: usize, end: usize, count: usize) -> Bin {
+        return Bin { start, end, count };
+    }
+
+    #[test]
+    fn histogram_bins() {
+        assert_eq!(
+            histogram(&[1, 2, 3, 10, 10], 3),
+            vec![bin(1, 4, 3), bin(5, 8, 0), bin(9, 10, 2)]
+        );
+        assert_eq!(histogram(&[7, 7], 10), vec![bin(7, 7, 2)]);
+        assert_eq!(histogram(&[], 10), vec![]);
+    }
+
+    #[test]
+    fn histogram_extreme_spread() {
+        let width = usize::MAX / 3 + 1;
+        assert_eq!(
+            histogram(&[0, usize::MAX], 3),
+            vec![
+                bin(0, width - 1, 1),
+                bin(width, 2 * width - 1, 0),
+                bin(2 * width, usize::MAX, 1)
+            ]
+        );
+        assert_eq!(histogram(&[usize::MAX, 0], 1), vec![bin(0, usize::MAX, 2)]);
+        assert_eq!(
+            histogram(&[usize::MAX, usize::MAX - 1], 5),
+            vec![
+                bin(usize::MAX - 1, usize::MAX - 1, 1),
+                bin(usize::MAX, usize::MAX, 1)
+            ]
+        );
+    }
+
+    #[test]
+    fn report() {
+        let elves = [
+            Elf::new(0, vec![1000, 2000, 3000]),
+            Elf::new(1, vec![4000]),
+            Elf::new(2, vec![5000, 6000]),
+            Elf::new(3, vec![7000, 8000, 9000]),
+            Elf::new(4, vec![10000]),
+        ];
+
+        let report = Stats::new(&elves, 2).unwrap().to_string();
+
+        assert!(report.starts_with("5 elves\ncalories: least 4000 (elf 1), most 24000 (elf 3)\n"));
+        assert!(
+            report.contains("  min 1, max 3, mean 2.0, median 2.0"),
+            "{}",
+            report
+        );
+        assert!(report.contains(&format!("   4000 - 14000 | {} 4\n", "#".repeat(40))));
+        assert!(report.contains(&format!("  14001 - 24000 | {:<40} 1\n", "#".repeat(10))));
+        assert!(Stats::new(&[], 10).is_none());
+    }
+}
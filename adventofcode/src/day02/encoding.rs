@@ -0,0 +1,281 @@
+//! What the symbols of the two strategy guide columns stand for.
+
+use std::{fmt::Display, fs};
+
+use crate::{
+    common::config::Options,
+    day02::rock_paper_scissors::{Game, RoundOutcome, Shape},
+};
+
+#[derive(Debug, PartialEq)]
+pub enum Meaning {
+    Shape(Shape),
+    Outcome(RoundOutcome),
+}
+
+/// The opponent's column always holds shapes, the second column holds my
+/// shapes, the outcomes I'm asked for, or a mix of both.
+pub struct Encoding {
+    opponent: Vec<(String, Shape)>,
+    mine: Vec<(String, Meaning)>,
+}
+
+impl Encoding {
+    /// Both columns are shapes, with the symbols of the game.
+    pub fn part1(game: &Game) -> Self {
+        return Encoding {
+            opponent: opponent_symbols(game),
+            mine: game
+                .shapes()
+                .map(|shape| {
+                    let symbol = game.rules(shape).my_symbol.to_string();
+                    (symbol, Meaning::Shape(shape))
+                })
+                .collect(),
+        };
+    }
+
+    /// `X`, `Y` and `Z` ask to lose, draw or win.
+    pub fn part2(game: &Game) -> Self {
+        return Encoding {
+            opponent: opponent_symbols(game),
+            mine: vec![
+                ("X".to_string(), Meaning::Outcome(RoundOutcome::Lose)),
+                ("Y".to_string(), Meaning::Outcome(RoundOutcome::Draw)),
+                ("Z".to_string(), Meaning::Outcome(RoundOutcome::Win)),
+            ],
+        };
+    }
+
+    /// `--encoding=<path>` of a file holding a description, or
+    /// `--encoding=<description>` when no such file can be read.
+    pub fn from_options(game: &Game, options: &Options) -> Result<Option<Self>, String> {
+        let Some(value) = options.parse_value::<String>("encoding")? else {
+            return Ok(None);
+        };
+        let description = match fs::read_to_string(&value) {
+            Ok(description) => description,
+            // descriptions always hold a `=`, file names rarely do
+            Err(_) if value.contains('=') => value,
+            Err(err) => return Err(format!("could not read encoding {}: {}", value, err)),
+        };
+        return Ok(Some(Encoding::parse(game, &description)?));
+    }
+
+    /// `symbol=meaning` pairs for the opponent's column, then for mine, the
+    /// columns separated by `;` or a new line. A meaning is a shape name of the
+    /// game, `lose`, `draw` or `win`: `A=Rock B=Paper C=Scissors; X=lose Y=draw Z=win`.
+    pub fn parse(game: &Game, description: &str) -> Result<Self, EncodingError> {
+        let columns: Vec<&str> = description
+            .split(['\n', ';'])
+            .map(|column| column.trim())
+            .filter(|column| !column.is_empty() && !column.starts_with('#'))
+            .collect();
+        let [opponent, mine] = columns.as_slice() else {
+            return Err(EncodingError::ColumnCount(columns.len()));
+        };
+
+        let mut encoding = Encoding {
+            opponent: Vec::new(),
+            mine: Vec::new(),
+        };
+        for (symbol, meaning) in parse_column(game, opponent)? {
+            match meaning {
+                Meaning::Shape(shape) => encoding.opponent.push((symbol, shape)),
+                Meaning::Outcome(_) => return Err(EncodingError::OpponentOutcome(symbol)),
+            }
+        }
+        encoding.mine = parse_column(game, mine)?;
+        return Ok(encoding);
+    }
+
+    pub fn opponent_shape(&self, symbol: &str) -> Option<Shape> {
+        return self
+            .opponent
+            .iter()
+            .find(|(known, _)| known == symbol)
+            .map(|(_, shape)| *shape);
+    }
+
+    pub fn my_meaning(&self, symbol: &str) -> Option<&Meaning> {
+        return self
+            .mine
+            .iter()
+            .find(|(known, _)| known == symbol)
+            .map(|(_, meaning)| meaning);
+    }
+}
+
+fn opponent_symbols(game: &Game) -> Vec<(String, Shape)> {
+    return game
+        .shapes()
+        .map(|shape| (game.rules(shape).opponent_symbol.to_string(), shape))
+        .collect();
+}
+
+fn parse_column(game: &Game, column: &str) -> Result<Vec<(String, Meaning)>, EncodingError> {
+    let mut pairs: Vec<(String, Meaning)> = Vec::new();
+    for pair in column.split_whitespace() {
+        let Some((symbol, meaning)) = pair.split_once('=') else {
+            return Err(EncodingError::InvalidPair(pair.to_string()));
+        };
+        if symbol.is_empty() {
+            return Err(EncodingError::InvalidPair(pair.to_string()));
+        }
+        if pairs.iter().any(|(known, _)| known == symbol) {
+            return Err(EncodingError::DuplicateSymbol(symbol.to_string()));
+        }
+        let meaning = match meaning {
+            "lose" => Meaning::Outcome(RoundOutcome::Lose),
+            "draw" => Meaning::Outcome(RoundOutcome::Draw),
+            "win" => Meaning::Outcome(RoundOutcome::Win),
+            name => match game.shape(name) {
+                Some(shape) => Meaning::Shape(shape),
+                None => return Err(EncodingError::UnknownMeaning(meaning.to_string())),
+            },
+        };
+        pairs.push((symbol.to_string(), meaning));
+    }
+    return Ok(pairs);
+}
+
+#[derive(Debug)]
+pub enum EncodingError {
+    ColumnCount(usize),
+    InvalidPair(String),
+    DuplicateSymbol(String),
+    UnknownMeaning(String),
+    OpponentOutcome(String),
+}
+
+impl From<EncodingError> for String {
+    fn from(value: EncodingError) -> Self {
+        return value.to_string();
+    }
+}
+
+impl Display for EncodingError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        return match self {
+            EncodingError::ColumnCount(count) => {
+                write!(f, "an encoding describes 2 columns, not {}", count)
+            }
+            EncodingError::InvalidPair(pair) => {
+                write!(f, "expected symbol=meaning, got \"{}\"", pair)
+            }
+            EncodingError::DuplicateSymbol(symbol) => {
+                write!(f, "symbol {} is used twice in a column", symbol)
+            }
+            EncodingError::UnknownMeaning(meaning) => write!(
+                f,
+                "\"{}\" is neither a shape of the game nor lose, draw or win",
+                meaning
+            ),
+            EncodingError::OpponentOutcome(symbol) => {
+                write!(f, "opponent symbol {} must stand for a shape", symbol)
+            }
+        };
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{Encoding, Meaning};
+    use crate::{
+        common::config::Options,
+        day02::rock_paper_scissors::{Game, RoundOutcome},
+    };
+
+    #[test]
+    fn default_encodings() {
+        let game = Game::classic();
+        let shape = |name: &str| game.shape(name).unwrap();
+
+        let part1 = Encoding::part1(&game);
+        assert_eq!(part1.opponent_shape("C"), Some(shape("Scissors")));
+        assert_eq!(part1.my_meaning("Y"), Some(&Meaning::Shape(shape("Paper"))));
+        let part2 = Encoding::part2(&game);
+        assert_eq!(
+            part2.my_meaning("Y"),
+            Some(&Meaning::Outcome(RoundOutcome::Draw))
+        );
+        assert_eq!(part2.my_meaning("W"), None);
+    }
+
+    #[test]
+    fn parse_description() {
+        let game = Game::classic();
+        let shape = |name: &str| game.shape(name).unwrap();
+
+        let encoding = Encoding::parse(
+            &game,
+            "# opponent\nr=Rock p=Paper s=Scissors\n# me\nwin=win Y=Paper",
+        )
+        .unwrap();
+
+        assert_eq!(encoding.opponent_shape("s"), Some(shape("Scissors")));
+        assert_eq!(encoding.opponent_shape("A"), None);
+        assert_eq!(
+            encoding.my_meaning("win"),
+            Some(&Meaning::Outcome(RoundOutcome::Win))
+        );
+        assert_eq!(
+            encoding.my_meaning("Y"),
+            Some(&Meaning::Shape(shape("Paper")))
+        );
+    }
+
+    #[test]
+    fn invalid_descriptions() {
+        let game = Game::classic();
+        let error = |description: &str| match Encoding::parse(&game, description) {
+            Ok(_) => panic!("{} should be invalid", description),
+            Err(err) => err.to_string(),
+        };
+
+        assert_eq!(error("A=Rock"), "an encoding describes 2 columns, not 1");
+        assert_eq!(error("A=Rock; X"), "expected symbol=meaning, got \"X\"");
+        assert_eq!(
+            error("A=Rock A=Paper; X=win"),
+            "symbol A is used twice in a column"
+        );
+        assert_eq!(
+            error("A=Rock; X=Lizard"),
+            "\"Lizard\" is neither a shape of the game nor lose, draw or win"
+        );
+        assert_eq!(
+            error("A=win; X=Rock"),
+            "opponent symbol A must stand for a shape"
+        );
+    }
+
+    #[test]
+    fn from_options() {
+        let game = Game::classic();
+        let encoding = |value: &str| {
+            let (options, _) = Options::parse([format!("--encoding={}", value)].into_iter());
+            return Encoding::from_options(&game, &options).map(Option::unwrap);
+        };
+
+        let inline = encoding("A=Rock B=Paper C=Scissors; X=win").unwrap();
+        assert_eq!(
+            inline.my_meaning("X"),
+            Some(&Meaning::Outcome(RoundOutcome::Win))
+        );
+
+        // a file name with a `=` is still read as a file
+        let path = std::env::temp_dir().join("adventofcode_day02_encoding=test.txt");
+        std::fs::write(&path, "A=Rock B=Paper C=Scissors\nX=lose").unwrap();
+        let from_file = encoding(path.to_str().unwrap());
+        let _ = std::fs::remove_file(&path);
+        assert_eq!(
+            from_file.unwrap().my_meaning("X"),
+            Some(&Meaning::Outcome(RoundOutcome::Lose))
+        );
+
+        assert!(encoding("missing.txt")
+            .err()
+            .unwrap()
+            .starts_with("could not read encoding missing.txt"));
+    }
+}
@@ -0,0 +1,256 @@
+//! `--explain`: every round of a guide with its points, then totals.
+
+use std::fmt::Display;
+
+use crate::day02::{
+    encoding::Encoding,
+    parse_guide,
+    rock_paper_scissors::{Game, RoundOutcome, RoundShapes},
+    scoring::{RoundScore, Scorer, ScoringRules},
+};
+
+pub struct GuideLine {
+    pub line_number: usize,
+    pub text: String,
+    pub round: Result<(RoundShapes, RoundScore), &'static str>,
+}
+
+pub struct Explanation<'a> {
+    game: &'a Game,
+    pub lines: Vec<GuideLine>,
+}
+
+const OUTCOMES: [RoundOutcome; 3] = [RoundOutcome::Win, RoundOutcome::Draw, RoundOutcome::Lose];
+
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Totals {
+    pub rounds: usize,
+    pub shape_points: i64,
+    pub outcome_points: i64,
+    pub bonus: i64,
+    pub penalty: i64,
+}
+
+impl Totals {
+    fn add(&mut self, score: &RoundScore) {
+        self.rounds += 1;
+        self.shape_points += i64::from(score.shape_points);
+        self.outcome_points += i64::from(score.outcome_points);
+        self.bonus += i64::from(score.bonus);
+        self.penalty += i64::from(score.penalty);
+    }
+
+    pub fn total(&self) -> i64 {
+        return self.shape_points + self.outcome_points + self.bonus - self.penalty;
+    }
+}
+
+/// Scores the guide line by line, keeping the lines that don't parse.
+pub fn explain<'a>(
+    game: &'a Game,
+    rules: &ScoringRules,
+    encoding: &Encoding,
+    input: &str,
+) -> Explanation<'a> {
+    let mut scorer = Scorer::new(game, rules);
+    let lines = input
+        .lines()
+        .zip(parse_guide(game, encoding, input.lines()))
+        .enumerate()
+        .map(|(i, (text, round))| GuideLine {
+            line_number: i + 1,
+            text: text.to_string(),
+            round: round.map(|round| {
+                let score = scorer.score(&round);
+                (round, score)
+            }),
+        })
+        .collect();
+    return Explanation { game, lines };
+}
+
+impl Explanation<'_> {
+    fn scores(&self) -> impl Iterator<Item = &(RoundShapes, RoundScore)> {
+        return self
+            .lines
+            .iter()
+            .filter_map(|line| line.round.as_ref().ok());
+    }
+
+    /// In the order of `OUTCOMES`: win, draw, lose.
+    pub fn per_outcome(&self) -> Vec<Totals> {
+        let mut totals = vec![Totals::default(); OUTCOMES.len()];
+        for (_, score) in self.scores() {
+            let index = OUTCOMES
+                .iter()
+                .position(|outcome| *outcome == score.outcome);
+            totals[index.expect("every outcome is listed")].add(score);
+        }
+        return totals;
+    }
+
+    /// Indexed by my shape.
+    pub fn per_shape(&self) -> Vec<Totals> {
+        let mut totals = vec![Totals::default(); self.game.shapes().count()];
+        for (round, score) in self.scores() {
+            totals[round.my_shape.0].add(score);
+        }
+        return totals;
+    }
+}
+
+/// The lines of an explanation without the totals, for `--per-round`.
+pub struct Rounds<'a>(&'a Explanation<'a>);
+
+impl Explanation<'_> {
+    pub fn rounds(&self) -> Rounds<'_> {
+        return Rounds(self);
+    }
+}
+
+impl Display for Rounds<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let game = self.0.game;
+        let mut total = 0;
+        for line in &self.0.lines {
+            let (round, score) = match &line.round {
+                Ok(round) => round,
+                Err(err) => {
+                    writeln!(f, "line {}: \"{}\": {}", line.line_number, line.text, err)?;
+                    continue;
+                }
+            };
+            total += score.total();
+            write!(
+                f,
+                "line {}: {} against {}, {:?}: {} shape + {} outcome",
+                line.line_number,
+                game.rules(round.my_shape).name,
+                game.rules(round.opponent_shape).name,
+                score.outcome,
+                score.shape_points,
+                score.outcome_points
+            )?;
+            if score.bonus != 0 {
+                write!(f, " + {} bonus", score.bonus)?;
+            }
+            if score.penalty != 0 {
+                write!(f, " - {} penalty", score.penalty)?;
+            }
+            writeln!(f, " = {} (total {})", score.total(), total)?;
+        }
+        return Ok(());
+    }
+}
+
+impl Display for Explanation<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.rounds())?;
+
+        writeln!(f, "per outcome:")?;
+        for (outcome, totals) in OUTCOMES.iter().zip(self.per_outcome()) {
+            write_totals(f, &format!("{:?}", outcome), &totals)?;
+        }
+        writeln!(f, "per shape:")?;
+        for (shape, totals) in self.game.shapes().zip(self.per_shape()) {
+            write_totals(f, &self.game.rules(shape).name, &totals)?;
+        }
+        let errors = self.lines.iter().filter(|line| line.round.is_err()).count();
+        if errors > 0 {
+            writeln!(f, "malformed lines: {}", errors)?;
+        }
+        return Ok(());
+    }
+}
+
+fn write_totals(f: &mut std::fmt::Formatter<'_>, name: &str, totals: &Totals) -> std::fmt::Result {
+    write!(
+        f,
+        "  {}: rounds {}, shape points {}, outcome points {}",
+        name, totals.rounds, totals.shape_points, totals.outcome_points
+    )?;
+    if totals.bonus != 0 || totals.penalty != 0 {
+        write!(f, ", bonus {}, penalty {}", totals.bonus, totals.penalty)?;
+    }
+    return writeln!(f, ", total {}", totals.total());
+}
+
+#[cfg(test)]
+mod test {
+    use super::explain;
+    use crate::day02::{encoding::Encoding, rock_paper_scissors::Game, scoring::ScoringRules};
+
+    #[test]
+    fn example_guide() {
+        let game = Game::classic();
+        let rules = ScoringRules::new(&game);
+
+        let explanation = explain(&game, &rules, &Encoding::part1(&game), "A Y\nB X\nC Z\n");
+
+        assert_eq!(
+            explanation.to_string(),
+            "\
+line 1: Paper against Rock, Win: 2 shape + 6 outcome = 8 (total 8)
+line 2: Rock against Paper, Lose: 1 shape + 0 outcome = 1 (total 9)
+line 3: Scissors against Scissors, Draw: 3 shape + 3 outcome = 6 (total 15)
+per outcome:
+  Win: rounds 1, shape points 2, outcome points 6, total 8
+  Draw: rounds 1, shape points 3, outcome points 3, total 6
+  Lose: rounds 1, shape points 1, outcome points 0, total 1
+per shape:
+  Rock: rounds 1, shape points 1, outcome points 0, total 1
+  Scissors: rounds 1, shape points 3, outcome points 3, total 6
+  Paper: rounds 1, shape points 2, outcome points 6, total 8
+"
+        );
+    }
+
+    #[test]
+    fn malformed_lines_are_shown() {
+        let game = Game::classic();
+        let rules = ScoringRules::parse(&game, "streak 2 10\nrepeat 1").unwrap();
+
+        let explanation = explain(&game, &rules, &Encoding::part1(&game), "C X\nC Q\nC X\n");
+
+        let out = explanation.to_string();
+        let lines: Vec<&str> = out.lines().collect();
+        assert_eq!(
+            lines[1],
+            "line 2: \"C Q\": expected player shape or round outcome"
+        );
+        assert_eq!(
+            lines[2],
+            "line 3: Rock against Scissors, Win: 1 shape + 6 outcome + 10 bonus - 1 penalty \
+             = 16 (total 23)"
+        );
+        assert_eq!(
+            lines[4],
+            "  Win: rounds 2, shape points 2, outcome points 12, bonus 10, penalty 1, total 23"
+        );
+        assert_eq!(lines.last(), Some(&"malformed lines: 1"));
+        assert_eq!(explanation.per_shape()[0].rounds, 2);
+    }
+
+    #[test]
+    fn per_round() {
+        let game = Game::classic();
+        let rules = ScoringRules::parse(&game, "streak 2 10\nrepeat 1").unwrap();
+
+        let explanation = explain(
+            &game,
+            &rules,
+            &Encoding::part1(&game),
+            "C X\nC Q\nC X\nA Y\n",
+        );
+
+        assert_eq!(
+            explanation.rounds().to_string(),
+            "\
+line 1: Rock against Scissors, Win: 1 shape + 6 outcome = 7 (total 7)
+line 2: \"C Q\": expected player shape or round outcome
+line 3: Rock against Scissors, Win: 1 shape + 6 outcome + 10 bonus - 1 penalty = 16 (total 23)
+line 4: Paper against Rock, Win: 2 shape + 6 outcome + 10 bonus = 18 (total 41)
+"
+        );
+    }
+}
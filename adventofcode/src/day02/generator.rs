@@ -0,0 +1,35 @@
+//! Random day 2 inputs: `A Y` strategy guide lines.
+
+use crate::common::rng::Rng;
+
+pub fn generate(rng: &mut Rng, round_count: usize) -> String {
+    let mut out = String::with_capacity(round_count * 4);
+    for _ in 0..round_count {
+        out.push(*rng.choose(&['A', 'B', 'C']));
+        out.push(' ');
+        out.push(*rng.choose(&['X', 'Y', 'Z']));
+        out.push('\n');
+    }
+    return out;
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        common::rng::Rng,
+        day02::{encoding::Encoding, parse_guide, rock_paper_scissors::Game},
+    };
+
+    #[test]
+    fn generated_input_parses() {
+        let input = super::generate(&mut Rng::new(2), 100);
+        let game = Game::classic();
+
+        let rounds: Vec<_> = parse_guide(&game, &Encoding::part1(&game), input.lines()).collect();
+        assert_eq!(rounds.len(), 100);
+        assert!(rounds.iter().all(|round| round.is_ok()));
+        assert!(
+            parse_guide(&game, &Encoding::part2(&game), input.lines()).all(|round| round.is_ok())
+        );
+    }
+}
@@ -1,140 +1,309 @@
-use crate::common::config::FileContentConfig;
-use crate::day02::rock_paper_scissors::{RoundOutcome, RoundShapes, Shape};
+use crate::common::config::{FileContentConfig, Options};
+use crate::day02::encoding::{Encoding, Meaning};
+use crate::day02::rock_paper_scissors::{Game, RoundShapes, Shape};
+use crate::day02::scoring::{Scorer, ScoringRules};
 
-mod rock_paper_scissors;
+mod encoding;
+mod explain;
+pub mod generator;
+pub mod rock_paper_scissors;
+mod scoring;
+mod solver;
+mod tournament;
 
 /// Solve day 2 https://adventofcode.com/2022/day/2
-pub fn solve(config: FileContentConfig) -> Result<(), &'static str> {
+pub fn solve(config: FileContentConfig) -> Result<(), String> {
     println!("Day 2!");
     // println!("{:?}", config);
+    let game = Game::from_options(&config.options)?;
+    let rules = ScoringRules::from_options(&game, &config.options)?;
+    let explain = config.options.has("explain");
+    let per_round = config.options.has("per-round");
 
-    let lines = config.content.lines();
-    let moves = parse_moves_part1(lines);
-    let score_part1 = get_score(moves);
-    println!("Expected score for part 1: {}", score_part1);
+    // the reports of both parts read alike, each is titled
+    let score = |title: &str, encoding: &Encoding| -> Result<i64, String> {
+        if explain || per_round {
+            println!("{}:", title);
+        }
+        if explain {
+            print!(
+                "{}",
+                explain::explain(&game, &rules, encoding, &config.content)
+            );
+        } else if per_round {
+            let explanation = explain::explain(&game, &rules, encoding, &config.content);
+            print!("{}", explanation.rounds());
+        }
+        return total_score(
+            &game,
+            &rules,
+            parse_guide(&game, encoding, config.content.lines()),
+        );
+    };
 
-    let lines = config.content.lines();
-    let moves = parse_moves_part2(lines);
-    let score_part2 = get_score(moves);
-    println!("Expected score for part 2: {}", score_part2);
+    // a guide in its own symbols would not parse with the puzzle's encodings
+    let encoding = Encoding::from_options(&game, &config.options)?;
+    if let Some(encoding) = &encoding {
+        println!(
+            "Expected score with the given encoding: {}",
+            score("given encoding", encoding)?
+        );
+    } else {
+        // both parts are reported before failing on a malformed line
+        let score_part1 = score("part 1", &Encoding::part1(&game));
+        if let Ok(score_part1) = &score_part1 {
+            println!("Expected score for part 1: {}", score_part1);
+        }
+        let score_part2 = score("part 2", &Encoding::part2(&game));
+        score_part1?;
+        println!("Expected score for part 2: {}", score_part2?);
+    }
+
+    if config.options.has("solve") {
+        // the opponent's column reads the same in both parts
+        let part1 = Encoding::part1(&game);
+        let opponent = parse_guide(
+            &game,
+            encoding.as_ref().unwrap_or(&part1),
+            config.content.lines(),
+        )
+        .map(|round| round.map(|round| round.opponent_shape))
+        .collect::<Result<Vec<_>, _>>()?;
+        print!("{}", solver::solve(&game, &rules, &opponent)?);
+    }
+
+    if config.options.has("tournament") {
+        // the guide bot plays the guide as the elf meant it, unless told otherwise
+        let encoding = encoding.unwrap_or_else(|| Encoding::part2(&game));
+        let guide = my_shapes(&game, &encoding, &config.content)?;
+        let seed = config.options.parse_value("seed")?.unwrap_or(0);
+        let rounds = config.options.parse_value("rounds")?.unwrap_or(1000);
+        let bots = tournament::bots(guide, seed);
+        print!("{}", tournament::round_robin(&game, &rules, &bots, rounds));
+    }
 
     return Ok(());
 }
 
-fn get_score(moves: impl Iterator<Item = Result<RoundShapes, &'static str>>) -> i32 {
-    let mut score = 0;
+pub const SOLVER_VERSION: &str = "1";
+/// Options changing the answers.
+pub const ANSWER_OPTIONS: &[&str] = &["game", "scoring"];
+/// Options printing more than the answers.
+pub const REPORT_OPTIONS: &[&str] = &["encoding", "explain", "per-round", "solve", "tournament"];
 
-    for round_shapes in moves {
-        let round_shapes = round_shapes.unwrap();
-        let outcome = round_shapes.my_shape.outcome(&round_shapes.opponent_shape);
-        score += match outcome {
-            RoundOutcome::Win => 6,
-            RoundOutcome::Draw => 3,
-            RoundOutcome::Lose => 0,
-        };
-        score += match round_shapes.my_shape {
-            Shape::Rock => 1,
-            Shape::Paper => 2,
-            Shape::Scissors => 3,
-        };
+pub fn answer(part: u8, input: &str, options: &Options) -> Result<String, String> {
+    let game = Game::from_options(options)?;
+    let encoding = if part == 1 {
+        Encoding::part1(&game)
+    } else {
+        Encoding::part2(&game)
+    };
+    let rules = ScoringRules::from_options(&game, options)?;
+    let score = total_score(&game, &rules, parse_guide(&game, &encoding, input.lines()))?;
+    return Ok(score.to_string());
+}
+
+/// The score of the rounds, or the first malformed one.
+fn total_score(
+    game: &Game,
+    rules: &ScoringRules,
+    moves: impl Iterator<Item = Result<RoundShapes, &'static str>>,
+) -> Result<i64, String> {
+    let mut scorer = Scorer::new(game, rules);
+    let mut score: i64 = 0;
+
+    for (i, round_shapes) in moves.enumerate() {
+        let round_shapes = round_shapes.map_err(|err| format!("line {}: {}", i + 1, err))?;
+        score = score
+            .checked_add(scorer.score(&round_shapes).total())
+            .ok_or_else(|| format!("line {}: the score is too large to add up", i + 1))?;
     }
 
-    return score;
+    return Ok(score);
 }
 
-fn parse_moves_part1<'a>(
-    input: impl Iterator<Item = &'a str> + 'a,
-) -> impl Iterator<Item = Result<RoundShapes, &'static str>> + 'a {
-    return input.map(|line| {
-        let mut split = line.split(" ");
-        let opponent_char: Option<char> = match split.next() {
-            None => return Err("expected round outcome"),
-            Some(shape_str) => shape_str.chars().next(),
-        };
-        let opponent_shape = match opponent_char {
-            Some('A') => Shape::Rock,
-            Some('B') => Shape::Paper,
-            Some('C') => Shape::Scissors,
-            _ => return Err("expected opponent shape"),
-        };
-        let my_char: Option<char> = match split.next() {
-            None => return Err("expected round outcome"),
-            Some(shape_str) => shape_str.chars().next(),
-        };
-        let my_shape = match my_char {
-            Some('X') => Shape::Rock,
-            Some('Y') => Shape::Paper,
-            Some('Z') => Shape::Scissors,
-            _ => return Err("expected player shape"),
-        };
-
-        return Ok(RoundShapes {
-            opponent_shape,
-            my_shape,
-        });
-    });
+/// The shapes the guide tells me to play, or the first malformed line.
+fn my_shapes(game: &Game, encoding: &Encoding, input: &str) -> Result<Vec<Shape>, String> {
+    return parse_guide(game, encoding, input.lines())
+        .enumerate()
+        .map(|(i, round)| match round {
+            Ok(round) => Ok(round.my_shape),
+            Err(err) => Err(format!("line {}: {}", i + 1, err)),
+        })
+        .collect();
 }
 
-fn parse_moves_part2<'a>(
+/// Reads the guide lines as `<opponent symbol> <my symbol>`.
+fn parse_guide<'a>(
+    game: &'a Game,
+    encoding: &'a Encoding,
     input: impl Iterator<Item = &'a str> + 'a,
 ) -> impl Iterator<Item = Result<RoundShapes, &'static str>> + 'a {
-    return input.map(|line| {
-        let mut split = line.split(" ");
-        let opponent_char: Option<char> = match split.next() {
-            None => return Err("expected round outcome"),
-            Some(shape_str) => shape_str.chars().next(),
-        };
-        let opponent_shape = match opponent_char {
-            Some('A') => Shape::Rock,
-            Some('B') => Shape::Paper,
-            Some('C') => Shape::Scissors,
-            _ => return Err("expected opponent shape"),
-        };
-        let outcome_char: Option<char> = match split.next() {
-            None => return Err("expected round outcome"),
-            Some(shape_str) => shape_str.chars().next(),
-        };
-        let shapes = [Shape::Rock, Shape::Paper, Shape::Scissors];
-        let my_shape = match outcome_char {
-            Some('X') => shapes
-                .into_iter()
-                .find(|me| me.outcome(&opponent_shape) == RoundOutcome::Lose)
-                .unwrap(),
-            Some('Y') => shapes
-                .into_iter()
-                .find(|me| me.outcome(&opponent_shape) == RoundOutcome::Draw)
-                .unwrap(),
-            Some('Z') => shapes
-                .into_iter()
-                .find(|me| me.outcome(&opponent_shape) == RoundOutcome::Win)
-                .unwrap(),
-            _ => return Err("expected round outcome"),
-        };
+    return input.map(|line| parse_round(game, encoding, line));
+}
+
+fn parse_round(game: &Game, encoding: &Encoding, line: &str) -> Result<RoundShapes, &'static str> {
+    let mut split = line.split_whitespace();
+    let opponent_shape = match split.next() {
+        None => return Err("expected opponent shape"),
+        Some(symbol) => match encoding.opponent_shape(symbol) {
+            Some(shape) => shape,
+            None => return Err("expected opponent shape"),
+        },
+    };
+    let my_shape = match split.next().map(|symbol| encoding.my_meaning(symbol)) {
+        None => return Err("expected player shape or round outcome"),
+        Some(Some(Meaning::Shape(shape))) => *shape,
+        Some(Some(Meaning::Outcome(outcome))) => game.shape_for(outcome, opponent_shape),
+        Some(None) => return Err("expected player shape or round outcome"),
+    };
 
-        return Ok(RoundShapes {
-            opponent_shape,
-            my_shape,
-        });
+    return Ok(RoundShapes {
+        opponent_shape,
+        my_shape,
     });
 }
 
 #[cfg(test)]
 mod test {
-    use crate::day02::{get_score, parse_moves_part1, parse_moves_part2};
+    use crate::common::config::{FileContentConfig, Options};
+    use crate::day02::encoding::Encoding;
+    use crate::day02::rock_paper_scissors::{Game, PRESETS};
+    use crate::day02::scoring::ScoringRules;
+    use crate::day02::{my_shapes, parse_guide, solve, total_score};
 
     #[test]
     fn part1_example() {
-        let input = String::from("A Y\nB X\nC Z\n");
-        let lines = input.lines();
-        let moves = parse_moves_part1(lines);
-        assert_eq!(15, get_score(moves));
+        let game = Game::classic();
+        let rules = ScoringRules::new(&game);
+        let encoding = Encoding::part1(&game);
+        let moves = parse_guide(&game, &encoding, "A Y\nB X\nC Z\n".lines());
+        assert_eq!(total_score(&game, &rules, moves), Ok(15));
     }
 
     #[test]
     fn part2_example() {
-        let input = String::from("A Y\nB X\nC Z\n");
-        let lines = input.lines();
-        let moves = parse_moves_part2(lines);
-        assert_eq!(12, get_score(moves));
+        let game = Game::classic();
+        let rules = ScoringRules::new(&game);
+        let encoding = Encoding::part2(&game);
+        let moves = parse_guide(&game, &encoding, "A Y\nB X\nC Z\n".lines());
+        assert_eq!(total_score(&game, &rules, moves), Ok(12));
+    }
+
+    #[test]
+    fn lizard_spock_guide() {
+        let game = Game::parse(PRESETS[1].1).unwrap();
+        let rules = ScoringRules::new(&game);
+        // spock vaporizes rock, lizard poisons spock, scissors decapitate lizard
+        let input = "A W\nE V\nD Z\n";
+
+        let (part1, part2) = (Encoding::part1(&game), Encoding::part2(&game));
+
+        let part1 = total_score(&game, &rules, parse_guide(&game, &part1, input.lines()));
+        assert_eq!(part1, Ok((6 + 5) + (6 + 4) + (6 + 3)));
+        // paper comes before spock to beat rock, scissors before lizard to lose
+        let part2 = total_score(
+            &game,
+            &rules,
+            parse_guide(&game, &part2, "A Z\nA X\nA Y\n".lines()),
+        );
+        assert_eq!(part2, Ok((6 + 2) + 3 + (3 + 1)));
+    }
+
+    #[test]
+    fn custom_encoding() {
+        let game = Game::classic();
+        let rules = ScoringRules::new(&game);
+        let encoding =
+            Encoding::parse(&game, "r=Rock p=Paper s=Scissors; l=lose d=draw S=Scissors").unwrap();
+
+        let rounds: Vec<_> = parse_guide(&game, &encoding, "r d\np S\ns l\n".lines()).collect();
+        assert!(rounds.iter().all(|round| round.is_ok()));
+        // rock against rock, scissors against paper, paper against scissors
+        let score = total_score(&game, &rules, rounds.into_iter());
+        assert_eq!(score, Ok((3 + 1) + (6 + 3) + 2));
+
+        let mut rounds = parse_guide(&game, &encoding, "A X\nr\nr x\n".lines());
+        assert_eq!(
+            rounds.next().unwrap().err(),
+            Some("expected opponent shape")
+        );
+        assert_eq!(
+            rounds.next().unwrap().err(),
+            Some("expected player shape or round outcome")
+        );
+        assert!(rounds.next().unwrap().is_err());
+
+        let moves = parse_guide(&game, &encoding, "r d\nr x\n".lines());
+        assert_eq!(
+            total_score(&game, &rules, moves),
+            Err("line 2: expected player shape or round outcome".to_string())
+        );
+    }
+
+    #[test]
+    fn solve_custom_symbols() {
+        let args = [
+            "--encoding=r=Rock p=Paper s=Scissors; l=lose d=draw S=Scissors",
+            "--solve",
+            "--tournament",
+            "--rounds=10",
+        ];
+        let config = |content: &str| FileContentConfig {
+            content: content.to_string(),
+            options: Options::parse(args.map(String::from).into_iter()).0,
+        };
+
+        assert_eq!(solve(config("r d\np S\ns l\n")), Ok(()));
+        assert_eq!(
+            solve(config("r d\nA X\n")),
+            Err("line 2: expected opponent shape".to_string())
+        );
+    }
+
+    #[test]
+    fn explain_custom_symbols() {
+        let encoding = "--encoding=r=Rock p=Paper s=Scissors; l=lose d=draw S=Scissors";
+        let config = |content: &str, report: &str| FileContentConfig {
+            content: content.to_string(),
+            options: Options::parse([encoding, report].map(String::from).into_iter()).0,
+        };
+
+        assert_eq!(solve(config("r d\np S\n", "--explain")), Ok(()));
+        assert_eq!(solve(config("r d\np S\n", "--per-round")), Ok(()));
+        assert_eq!(
+            solve(config("r d\np x\n", "--explain")),
+            Err("line 2: expected player shape or round outcome".to_string())
+        );
+    }
+
+    #[test]
+    fn large_points() {
+        let game = Game::classic();
+        let rules =
+            ScoringRules::parse(&game, "outcome win 2000000000\nstreak 1 2147483647").unwrap();
+        let encoding = Encoding::part1(&game);
+        let moves = parse_guide(&game, &encoding, "A Y\nA Y\n".lines());
+
+        assert_eq!(
+            total_score(&game, &rules, moves),
+            Ok(2 * (2 + 2_000_000_000 + 2_147_483_647))
+        );
+    }
+
+    #[test]
+    fn guide_shapes() {
+        let game = Game::classic();
+        let encoding = Encoding::part2(&game);
+        let rock = game.shape("Rock").unwrap();
+
+        assert_eq!(
+            my_shapes(&game, &encoding, "A Y\nB X\n"),
+            Ok(vec![rock, rock])
+        );
+        assert_eq!(
+            my_shapes(&game, &encoding, "A Y\nB W\nC Z\n"),
+            Err("line 2: expected player shape or round outcome".to_string())
+        );
     }
 }
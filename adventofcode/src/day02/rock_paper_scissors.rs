@@ -1,3 +1,7 @@
+use std::{fmt::Display, fs};
+
+use crate::common::config::Options;
+
 #[derive(Debug, PartialEq)]
 pub enum RoundOutcome {
     Win,
@@ -5,48 +9,334 @@ pub enum RoundOutcome {
     Draw,
 }
 
-#[derive(Debug, PartialEq)]
-pub enum Shape {
-    Rock,
-    Paper,
-    Scissors,
-}
+/// A shape of a `Game`, by its position in the game's cyclic order.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Shape(pub usize);
 
 pub struct RoundShapes {
     pub opponent_shape: Shape,
     pub my_shape: Shape,
 }
 
-impl Shape {
-    pub(crate) fn outcome(&self, other: &Self) -> RoundOutcome {
+pub struct ShapeRules {
+    pub name: String,
+    /// Guide symbols for the opponent's and for my shape.
+    pub opponent_symbol: char,
+    pub my_symbol: char,
+    pub points: i32,
+}
+
+/// A game of an odd number of shapes in cyclic order, where each shape beats
+/// the next (n - 1) / 2 shapes and loses to the others.
+pub struct Game {
+    shapes: Vec<ShapeRules>,
+    pub win_points: i32,
+    pub draw_points: i32,
+    pub lose_points: i32,
+}
+
+pub const PRESETS: [(&str, &str); 3] = [
+    ("classic", CLASSIC),
+    ("rpsls", ROCK_PAPER_SCISSORS_LIZARD_SPOCK),
+    ("rps7", ROCK_PAPER_SCISSORS_7),
+];
+
+const CLASSIC: &str = "\
+# name, opponent symbol, my symbol, points
+shape Rock A X 1
+shape Scissors C Z 3
+shape Paper B Y 2
+outcome win 6
+outcome draw 3
+outcome lose 0
+";
+
+const ROCK_PAPER_SCISSORS_LIZARD_SPOCK: &str = "\
+shape Rock A X 1
+shape Scissors C Z 3
+shape Lizard D V 4
+shape Paper B Y 2
+shape Spock E W 5
+outcome win 6
+outcome draw 3
+outcome lose 0
+";
+
+const ROCK_PAPER_SCISSORS_7: &str = "\
+shape Rock A X 1
+shape Fire D T 4
+shape Scissors C Z 3
+shape Sponge E U 5
+shape Paper B Y 2
+shape Air F V 6
+shape Water G W 7
+outcome win 6
+outcome draw 3
+outcome lose 0
+";
+
+impl Game {
+    /// Rock, paper, scissors, with the puzzle's symbols and points.
+    pub fn classic() -> Self {
+        return Game::parse(CLASSIC).expect("classic game is valid");
+    }
+
+    /// A preset name from `--game=<name>`, or the path of a game description.
+    pub fn from_options(options: &Options) -> Result<Self, String> {
+        let Some(name) = options.parse_value::<String>("game")? else {
+            return Ok(Game::classic());
+        };
+        if let Some((_, description)) = PRESETS.iter().find(|(preset, _)| *preset == name) {
+            return Ok(Game::parse(description)?);
+        }
+        let description = fs::read_to_string(&name)
+            .map_err(|err| format!("could not read game {}: {}", name, err))?;
+        return Ok(Game::parse(&description)?);
+    }
+
+    /// One `shape <name> <opponent symbol> <my symbol> <points>` line per
+    /// shape in cyclic order and `outcome <win|draw|lose> <points>` lines.
+    /// Text after a `#` is a comment.
+    pub fn parse(description: &str) -> Result<Self, GameError> {
+        let mut game = Game {
+            shapes: Vec::new(),
+            win_points: 0,
+            draw_points: 0,
+            lose_points: 0,
+        };
+
+        for (i, line) in description.lines().enumerate() {
+            let line_number = i + 1;
+            let invalid = || GameError::InvalidLine(line_number, line.trim().to_string());
+            let text = line.split('#').next().unwrap_or_default();
+            let words: Vec<&str> = text.split_whitespace().collect();
+            match words.as_slice() {
+                [] => (),
+                ["shape", name, opponent_symbol, my_symbol, points] => {
+                    let (Some(opponent_symbol), Some(my_symbol)) =
+                        (single_char(opponent_symbol), single_char(my_symbol))
+                    else {
+                        return Err(invalid());
+                    };
+                    if game.shapes.iter().any(|shape| shape.name == *name) {
+                        return Err(GameError::DuplicateShape(name.to_string()));
+                    }
+                    let symbols = |shape: &ShapeRules| (shape.opponent_symbol, shape.my_symbol);
+                    for (known_opponent, known_mine) in game.shapes.iter().map(symbols) {
+                        if known_opponent == opponent_symbol {
+                            return Err(GameError::DuplicateSymbol(opponent_symbol));
+                        }
+                        if known_mine == my_symbol {
+                            return Err(GameError::DuplicateSymbol(my_symbol));
+                        }
+                    }
+                    game.shapes.push(ShapeRules {
+                        name: name.to_string(),
+                        opponent_symbol,
+                        my_symbol,
+                        points: points.parse().map_err(|_| invalid())?,
+                    });
+                }
+                ["outcome", outcome, points] => {
+                    let points = points.parse().map_err(|_| invalid())?;
+                    match *outcome {
+                        "win" => game.win_points = points,
+                        "draw" => game.draw_points = points,
+                        "lose" => game.lose_points = points,
+                        _ => return Err(invalid()),
+                    }
+                }
+                _ => return Err(invalid()),
+            }
+        }
+
+        if game.shapes.len() < 3 || game.shapes.len().is_multiple_of(2) {
+            return Err(GameError::ShapeCount(game.shapes.len()));
+        }
+        return Ok(game);
+    }
+
+    pub fn shapes(&self) -> impl Iterator<Item = Shape> {
+        return (0..self.shapes.len()).map(Shape);
+    }
+
+    pub fn rules(&self, shape: Shape) -> &ShapeRules {
+        return &self.shapes[shape.0];
+    }
+
+    /// The shape called `name`, if the game has one.
+    pub fn shape(&self, name: &str) -> Option<Shape> {
+        return self.shapes().find(|&shape| self.rules(shape).name == name);
+    }
+
+    pub fn outcome(&self, me: Shape, other: Shape) -> RoundOutcome {
+        let n = self.shapes.len();
+        let distance = (other.0 + n - me.0) % n;
+        return if distance == 0 {
+            RoundOutcome::Draw
+        } else if distance <= (n - 1) / 2 {
+            RoundOutcome::Win
+        } else {
+            RoundOutcome::Lose
+        };
+    }
+
+    /// The first shape, in cyclic order, giving me `outcome` against `other`.
+    pub fn shape_for(&self, outcome: &RoundOutcome, other: Shape) -> Shape {
+        return self
+            .shapes()
+            .find(|&me| self.outcome(me, other) == *outcome)
+            .expect("every outcome is possible");
+    }
+}
+
+fn single_char(text: &str) -> Option<char> {
+    let mut chars = text.chars();
+    return match (chars.next(), chars.next()) {
+        (Some(ch), None) => Some(ch),
+        _ => None,
+    };
+}
+
+#[derive(Debug)]
+pub enum GameError {
+    InvalidLine(usize, String), // line number, text
+    DuplicateShape(String),
+    /// Two shapes with the same symbol in a guide column.
+    DuplicateSymbol(char),
+    ShapeCount(usize),
+}
+
+impl From<GameError> for String {
+    fn from(value: GameError) -> Self {
+        return value.to_string();
+    }
+}
+
+impl Display for GameError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         return match self {
-            Shape::Rock => match other {
-                Shape::Rock => RoundOutcome::Draw,
-                Shape::Paper => RoundOutcome::Lose,
-                Shape::Scissors => RoundOutcome::Win,
-            },
-            Shape::Paper => match other {
-                Shape::Rock => RoundOutcome::Win,
-                Shape::Paper => RoundOutcome::Draw,
-                Shape::Scissors => RoundOutcome::Lose,
-            },
-            Shape::Scissors => match other {
-                Shape::Rock => RoundOutcome::Lose,
-                Shape::Paper => RoundOutcome::Win,
-                Shape::Scissors => RoundOutcome::Draw,
-            },
+            GameError::InvalidLine(line, text) => {
+                write!(f, "line {} of the game is invalid: \"{}\"", line, text)
+            }
+            GameError::DuplicateShape(name) => write!(f, "shape {} is defined twice", name),
+            GameError::DuplicateSymbol(symbol) => {
+                write!(f, "symbol {} stands for two shapes", symbol)
+            }
+            GameError::ShapeCount(count) => write!(
+                f,
+                "a game needs an odd number of shapes, at least 3, not {}",
+                count
+            ),
         };
     }
 }
 
 #[cfg(test)]
 mod test {
-    use super::{RoundOutcome, Shape};
+    use super::{Game, RoundOutcome, PRESETS};
 
     #[test]
     fn shape_outcome() {
-        assert_eq!(RoundOutcome::Win, Shape::Rock.outcome(&Shape::Scissors));
-        assert_eq!(RoundOutcome::Lose, Shape::Rock.outcome(&Shape::Paper));
-        assert_eq!(RoundOutcome::Draw, Shape::Rock.outcome(&Shape::Rock));
+        let game = Game::classic();
+        let shape = |name: &str| game.shape(name).unwrap();
+        assert_eq!(
+            RoundOutcome::Win,
+            game.outcome(shape("Rock"), shape("Scissors"))
+        );
+        assert_eq!(
+            RoundOutcome::Lose,
+            game.outcome(shape("Rock"), shape("Paper"))
+        );
+        assert_eq!(
+            RoundOutcome::Draw,
+            game.outcome(shape("Rock"), shape("Rock"))
+        );
+    }
+
+    #[test]
+    fn classic_rules() {
+        let game = Game::classic();
+        let paper = game.rules(game.shape("Paper").unwrap());
+        assert_eq!((paper.opponent_symbol, paper.my_symbol), ('B', 'Y'));
+        assert_eq!(paper.points, 2);
+        assert_eq!(game.rules(game.shape("Scissors").unwrap()).points, 3);
+        assert_eq!(game.shape("Lizard"), None);
+    }
+
+    #[test]
+    fn presets_are_balanced() {
+        for (name, description) in PRESETS {
+            let game = Game::parse(description).unwrap();
+            for me in game.shapes() {
+                let wins = game
+                    .shapes()
+                    .filter(|&other| game.outcome(me, other) == RoundOutcome::Win)
+                    .count();
+                let losses = game
+                    .shapes()
+                    .filter(|&other| game.outcome(other, me) == RoundOutcome::Win)
+                    .count();
+                assert_eq!(wins, (game.shapes.len() - 1) / 2, "{}", name);
+                assert_eq!(losses, wins, "{}", name);
+            }
+        }
+    }
+
+    #[test]
+    fn lizard_spock() {
+        let game = Game::parse(PRESETS[1].1).unwrap();
+        let shape = |name: &str| game.shape(name).unwrap();
+
+        assert_eq!(
+            game.outcome(shape("Spock"), shape("Scissors")),
+            RoundOutcome::Win
+        );
+        assert_eq!(
+            game.outcome(shape("Spock"), shape("Rock")),
+            RoundOutcome::Win
+        );
+        assert_eq!(
+            game.outcome(shape("Lizard"), shape("Spock")),
+            RoundOutcome::Win
+        );
+        assert_eq!(
+            game.outcome(shape("Lizard"), shape("Paper")),
+            RoundOutcome::Win
+        );
+        assert_eq!(
+            game.outcome(shape("Paper"), shape("Spock")),
+            RoundOutcome::Win
+        );
+        assert_eq!(
+            game.outcome(shape("Rock"), shape("Spock")),
+            RoundOutcome::Lose
+        );
+    }
+
+    #[test]
+    fn comments() {
+        let game = Game::parse(
+            "# cyclic order\nshape Rock A X 1 # first\nshape Scissors C Z 3\n\
+             shape Paper B Y 2\noutcome win 6#points",
+        )
+        .unwrap();
+        assert_eq!(game.rules(game.shape("Rock").unwrap()).points, 1);
+        assert_eq!(game.win_points, 6);
+    }
+
+    #[test]
+    fn invalid_games() {
+        assert!(Game::parse("shape Rock A X 1\nshape Paper B Y 2\n").is_err());
+        assert!(Game::parse("shape Rock A X 1\nshape Rock B Y 2\nshape S C Z 3").is_err());
+        let err = Game::parse("shape Rock A X 1\nshape Paper A Y 2\nshape S C Z 3").err();
+        assert_eq!(err.unwrap().to_string(), "symbol A stands for two shapes");
+        let err = Game::parse("shape Rock A X 1\nshape Paper B X 2\nshape S C Z 3").err();
+        assert_eq!(err.unwrap().to_string(), "symbol X stands for two shapes");
+        let err = Game::parse("shape Rock A X one").err().unwrap();
+        assert_eq!(
+            err.to_string(),
+            "line 1 of the game is invalid: \"shape Rock A X one\""
+        );
     }
 }
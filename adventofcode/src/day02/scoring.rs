@@ -0,0 +1,315 @@
+//! Points of a round: outcome and shape tables, streak bonuses and penalties.
+
+use std::{fmt::Display, fs};
+
+use crate::{
+    common::config::Options,
+    day02::rock_paper_scissors::{Game, RoundOutcome, RoundShapes, Shape},
+};
+
+pub struct ScoringRules {
+    pub win_points: i32,
+    pub draw_points: i32,
+    pub lose_points: i32,
+    /// Indexed by shape.
+    pub shape_points: Vec<i32>,
+    pub streak_bonus: Option<StreakBonus>,
+    /// Taken off when I play the same shape as in the previous round.
+    pub repeat_penalty: i32,
+}
+
+/// `points` more for each win making a streak of at least `wins` wins.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct StreakBonus {
+    pub wins: usize,
+    pub points: i32,
+}
+
+pub const PRESETS: [(&str, &str); 3] = [
+    ("puzzle", ""),
+    (
+        "flat",
+        "outcome win 1\noutcome draw 0\noutcome lose -1\nshapes 0\n",
+    ),
+    ("streaks", "streak 3 5\nrepeat 2\n"),
+];
+
+impl ScoringRules {
+    /// The points of the game's description, without bonus nor penalty.
+    pub fn new(game: &Game) -> Self {
+        return ScoringRules {
+            win_points: game.win_points,
+            draw_points: game.draw_points,
+            lose_points: game.lose_points,
+            shape_points: game
+                .shapes()
+                .map(|shape| game.rules(shape).points)
+                .collect(),
+            streak_bonus: None,
+            repeat_penalty: 0,
+        };
+    }
+
+    /// A preset name from `--scoring=<name>`, or the path of a rules file.
+    pub fn from_options(game: &Game, options: &Options) -> Result<Self, String> {
+        let Some(name) = options.parse_value::<String>("scoring")? else {
+            return Ok(ScoringRules::new(game));
+        };
+        if let Some((_, description)) = PRESETS.iter().find(|(preset, _)| *preset == name) {
+            return Ok(ScoringRules::parse(game, description)?);
+        }
+        let description = fs::read_to_string(&name)
+            .map_err(|err| format!("could not read scoring rules {}: {}", name, err))?;
+        return Ok(ScoringRules::parse(game, &description)?);
+    }
+
+    /// Changes to the game's points, one per line:
+    ///
+    /// ```text
+    /// outcome <win|draw|lose> <points>
+    /// shape <name> <points>
+    /// shapes <points>          every shape
+    /// streak <wins> <points>   bonus for each win making a streak of <wins> wins
+    /// repeat <points>          penalty for playing the previous shape again
+    /// ```
+    ///
+    /// The repeat penalty can't be negative. Text after a `#` is a comment.
+    pub fn parse(game: &Game, description: &str) -> Result<Self, ScoringError> {
+        let mut rules = ScoringRules::new(game);
+
+        for (i, line) in description.lines().enumerate() {
+            let invalid = || ScoringError::InvalidLine(i + 1, line.trim().to_string());
+            let text = line.split('#').next().unwrap_or_default();
+            let words: Vec<&str> = text.split_whitespace().collect();
+            match words.as_slice() {
+                [] => (),
+                ["outcome", outcome, points] => {
+                    let points = points.parse().map_err(|_| invalid())?;
+                    match *outcome {
+                        "win" => rules.win_points = points,
+                        "draw" => rules.draw_points = points,
+                        "lose" => rules.lose_points = points,
+                        _ => return Err(invalid()),
+                    }
+                }
+                ["shape", name, points] => {
+                    let Some(shape) = game.shape(name) else {
+                        return Err(ScoringError::UnknownShape(name.to_string()));
+                    };
+                    rules.shape_points[shape.0] = points.parse().map_err(|_| invalid())?;
+                }
+                ["shapes", points] => {
+                    let points = points.parse().map_err(|_| invalid())?;
+                    rules.shape_points.fill(points);
+                }
+                ["streak", wins, points] => {
+                    rules.streak_bonus = Some(StreakBonus {
+                        wins: wins.parse().map_err(|_| invalid())?,
+                        points: points.parse().map_err(|_| invalid())?,
+                    });
+                }
+                ["repeat", points] => {
+                    let points = points.parse().map_err(|_| invalid())?;
+                    if points < 0 {
+                        return Err(ScoringError::NegativePenalty(i + 1, points));
+                    }
+                    rules.repeat_penalty = points;
+                }
+                _ => return Err(invalid()),
+            }
+        }
+        return Ok(rules);
+    }
+
+    pub fn outcome_points(&self, outcome: &RoundOutcome) -> i32 {
+        return match outcome {
+            RoundOutcome::Win => self.win_points,
+            RoundOutcome::Draw => self.draw_points,
+            RoundOutcome::Lose => self.lose_points,
+        };
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub struct RoundScore {
+    pub outcome: RoundOutcome,
+    pub shape_points: i32,
+    pub outcome_points: i32,
+    pub bonus: i32,
+    pub penalty: i32,
+}
+
+impl RoundScore {
+    /// In i64, which four points out of i32 can't overflow.
+    pub fn total(&self) -> i64 {
+        return i64::from(self.shape_points)
+            + i64::from(self.outcome_points)
+            + i64::from(self.bonus)
+            - i64::from(self.penalty);
+    }
+}
+
+/// Scores the rounds of a match one after the other, keeping what the bonuses
+/// and penalties depend on.
+pub struct Scorer<'a> {
+    game: &'a Game,
+    rules: &'a ScoringRules,
+    win_streak: usize,
+    previous: Option<Shape>,
+}
+
+impl<'a> Scorer<'a> {
+    pub fn new(game: &'a Game, rules: &'a ScoringRules) -> Self {
+        return Scorer {
+            game,
+            rules,
+            win_streak: 0,
+            previous: None,
+        };
+    }
+
+    pub fn score(&mut self, round: &RoundShapes) -> RoundScore {
+        let outcome = self.game.outcome(round.my_shape, round.opponent_shape);
+        self.win_streak = match outcome {
+            RoundOutcome::Win => self.win_streak + 1,
+            _ => 0,
+        };
+        let bonus = match self.rules.streak_bonus {
+            Some(streak) if self.win_streak >= streak.wins => streak.points,
+            _ => 0,
+        };
+        let penalty = match self.previous {
+            Some(previous) if previous == round.my_shape => self.rules.repeat_penalty,
+            _ => 0,
+        };
+        self.previous = Some(round.my_shape);
+
+        return RoundScore {
+            shape_points: self.rules.shape_points[round.my_shape.0],
+            outcome_points: self.rules.outcome_points(&outcome),
+            outcome,
+            bonus,
+            penalty,
+        };
+    }
+}
+
+#[derive(Debug)]
+pub enum ScoringError {
+    InvalidLine(usize, String), // line number, text
+    UnknownShape(String),
+    NegativePenalty(usize, i32), // line number, points
+}
+
+impl From<ScoringError> for String {
+    fn from(value: ScoringError) -> Self {
+        return value.to_string();
+    }
+}
+
+impl Display for ScoringError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        return match self {
+            ScoringError::InvalidLine(line, text) => {
+                write!(
+                    f,
+                    "line {} of the scoring rules is invalid: \"{}\"",
+                    line, text
+                )
+            }
+            ScoringError::UnknownShape(name) => write!(f, "the game has no shape {}", name),
+            ScoringError::NegativePenalty(line, points) => write!(
+                f,
+                "line {} of the scoring rules: the repeat penalty can't be negative, not {}",
+                line, points
+            ),
+        };
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{RoundScore, Scorer, ScoringRules, StreakBonus, PRESETS};
+    use crate::day02::rock_paper_scissors::{Game, RoundOutcome, RoundShapes, Shape};
+
+    fn round(my_shape: Shape, opponent_shape: Shape) -> RoundShapes {
+        return RoundShapes {
+            opponent_shape,
+            my_shape,
+        };
+    }
+
+    #[test]
+    fn presets() {
+        let game = Game::classic();
+        for (name, description) in PRESETS {
+            assert!(ScoringRules::parse(&game, description).is_ok(), "{}", name);
+        }
+
+        let rules = ScoringRules::parse(&game, PRESETS[1].1).unwrap();
+        assert_eq!(rules.outcome_points(&RoundOutcome::Lose), -1);
+        assert_eq!(rules.shape_points, vec![0, 0, 0]);
+        let rules = ScoringRules::parse(&game, PRESETS[2].1).unwrap();
+        assert_eq!(rules.streak_bonus, Some(StreakBonus { wins: 3, points: 5 }));
+        assert_eq!(rules.shape_points, vec![1, 3, 2]);
+    }
+
+    #[test]
+    fn streaks_and_repeats() {
+        let game = Game::classic();
+        let rules =
+            ScoringRules::parse(&game, "streak 2 10\nrepeat 4 # per round\nshape Rock 7").unwrap();
+        let mut scorer = Scorer::new(&game, &rules);
+        let shape = |name: &str| game.shape(name).unwrap();
+
+        let totals: Vec<i64> = [
+            round(shape("Rock"), shape("Scissors")),
+            round(shape("Rock"), shape("Scissors")),
+            round(shape("Scissors"), shape("Rock")),
+            round(shape("Rock"), shape("Scissors")),
+        ]
+        .iter()
+        .map(|round| scorer.score(round).total())
+        .collect();
+
+        assert_eq!(totals, vec![13, 13 + 10 - 4, 3, 13]);
+    }
+
+    #[test]
+    fn round_details() {
+        let game = Game::classic();
+        let rules = ScoringRules::new(&game);
+        let shape = |name: &str| game.shape(name).unwrap();
+
+        let score = Scorer::new(&game, &rules).score(&round(shape("Scissors"), shape("Rock")));
+
+        assert_eq!(
+            score,
+            RoundScore {
+                outcome: RoundOutcome::Lose,
+                shape_points: 3,
+                outcome_points: 0,
+                bonus: 0,
+                penalty: 0,
+            }
+        );
+    }
+
+    #[test]
+    fn invalid_rules() {
+        let game = Game::classic();
+
+        let err = ScoringRules::parse(&game, "outcome tie 3").err().unwrap();
+        assert_eq!(
+            err.to_string(),
+            "line 1 of the scoring rules is invalid: \"outcome tie 3\""
+        );
+        let err = ScoringRules::parse(&game, "\nshape Spock 5").err().unwrap();
+        assert_eq!(err.to_string(), "the game has no shape Spock");
+        let err = ScoringRules::parse(&game, "repeat -2").err().unwrap();
+        assert_eq!(
+            err.to_string(),
+            "line 1 of the scoring rules: the repeat penalty can't be negative, not -2"
+        );
+    }
+}
@@ -0,0 +1,313 @@
+//! Best responses to the opponent column of a guide, and the Nash equilibrium
+//! of the game under the scoring rules, in exact fractions.
+//!
+//! Only the outcome and shape points count, streak bonuses and repeat
+//! penalties depend on the order of the rounds.
+
+use std::fmt::Display;
+
+use crate::{
+    common::{math::MathError, ratio::Ratio},
+    day02::{
+        rock_paper_scissors::{Game, Shape},
+        scoring::ScoringRules,
+    },
+};
+
+pub struct Solution {
+    pub names: Vec<String>,
+    pub counts: Vec<usize>,
+    /// Share of each opponent shape in the guide.
+    pub distribution: Vec<Ratio>,
+    /// Expected score per round of always playing each shape against them.
+    pub expected: Vec<Ratio>,
+    pub best_response: Shape,
+    /// My equilibrium strategy, the chance of playing each shape.
+    pub nash: Vec<Ratio>,
+    /// Expected score per round the Nash strategy guarantees whatever the opponent does.
+    pub value: Ratio,
+    /// Expected score per round of the Nash strategy against the guide's opponent.
+    pub nash_expected: Ratio,
+}
+
+/// My points for each of my shapes (rows) against each opponent shape (columns).
+pub fn payoffs(game: &Game, rules: &ScoringRules) -> Vec<Vec<i128>> {
+    return game
+        .shapes()
+        .map(|me| {
+            game.shapes()
+                .map(|other| {
+                    let outcome = game.outcome(me, other);
+                    rules.shape_points[me.0] as i128 + rules.outcome_points(&outcome) as i128
+                })
+                .collect()
+        })
+        .collect();
+}
+
+pub fn solve(game: &Game, rules: &ScoringRules, opponent: &[Shape]) -> Result<Solution, String> {
+    if opponent.is_empty() {
+        return Err("the guide has no rounds to learn the opponent from".to_string());
+    }
+    let payoffs = payoffs(game, rules);
+
+    let mut counts = vec![0; payoffs.len()];
+    for shape in opponent {
+        counts[shape.0] += 1;
+    }
+    let distribution = counts
+        .iter()
+        .map(|&count| Ratio::new(count as i128, opponent.len() as i128))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let expected = payoffs
+        .iter()
+        .map(|row| expectation(row, &distribution))
+        .collect::<Result<Vec<_>, _>>()?;
+    let mut best_response = Shape(0);
+    for (shape, score) in expected.iter().enumerate() {
+        if *score > expected[best_response.0] {
+            best_response = Shape(shape);
+        }
+    }
+
+    let (nash, value) = maximin(&payoffs)?;
+    let mut nash_expected = Ratio::ZERO;
+    for (row, chance) in payoffs.iter().zip(&nash) {
+        nash_expected =
+            nash_expected.checked_add(expectation(row, &distribution)?.checked_mul(*chance)?)?;
+    }
+
+    return Ok(Solution {
+        names: game
+            .shapes()
+            .map(|shape| game.rules(shape).name.clone())
+            .collect(),
+        counts,
+        distribution,
+        expected,
+        best_response,
+        nash,
+        value,
+        nash_expected,
+    });
+}
+
+fn expectation(points: &[i128], chances: &[Ratio]) -> Result<Ratio, MathError> {
+    let mut sum = Ratio::ZERO;
+    for (&points, chance) in points.iter().zip(chances) {
+        sum = sum.checked_add(Ratio::from_integer(points).checked_mul(*chance)?)?;
+    }
+    return Ok(sum);
+}
+
+/// The mixed strategy of the row player maximizing their worst expected
+/// payoff, and that payoff, the value of the zero-sum game.
+///
+/// The column player's linear program `max Σy, A'y ≤ 1, y ≥ 0`, with `A'` the
+/// payoffs shifted to be positive, is solved with the simplex method. Its dual
+/// values are the row player's strategy scaled by `1 / value'`.
+pub fn maximin(payoffs: &[Vec<i128>]) -> Result<(Vec<Ratio>, Ratio), MathError> {
+    let (rows, columns) = (payoffs.len(), payoffs[0].len());
+    let min = payoffs.iter().flatten().copied().min().unwrap_or(0);
+    let shift = 1 - min;
+
+    // constraint rows, then the objective row; columns are y, the slacks, then the bound
+    let width = columns + rows + 1;
+    let mut tableau = vec![vec![Ratio::ZERO; width]; rows + 1];
+    for (i, row) in payoffs.iter().enumerate() {
+        for (j, &payoff) in row.iter().enumerate() {
+            tableau[i][j] = Ratio::from_integer(payoff + shift);
+        }
+        tableau[i][columns + i] = Ratio::ONE;
+        tableau[i][width - 1] = Ratio::ONE;
+    }
+    tableau[rows][..columns].fill(Ratio::from_integer(-1));
+    let mut basis: Vec<usize> = (columns..columns + rows).collect();
+
+    // Bland's rule: lowest entering and leaving variables, so it can't cycle
+    while let Some(entering) = (0..width - 1).find(|&j| tableau[rows][j].is_negative()) {
+        let mut leaving: Option<(usize, Ratio)> = None;
+        for i in 0..rows {
+            let coefficient = tableau[i][entering];
+            if coefficient <= Ratio::ZERO {
+                continue;
+            }
+            let ratio = tableau[i][width - 1].checked_div(coefficient)?;
+            let better = match leaving {
+                None => true,
+                Some((best, best_ratio)) => {
+                    ratio < best_ratio || (ratio == best_ratio && basis[i] < basis[best])
+                }
+            };
+            if better {
+                leaving = Some((i, ratio));
+            }
+        }
+        // positive payoffs keep every y bounded
+        let (pivot_row, _) = leaving.expect("the program is bounded");
+        pivot(&mut tableau, pivot_row, entering)?;
+        basis[pivot_row] = entering;
+    }
+
+    let total = tableau[rows][width - 1];
+    let mut strategy = Vec::with_capacity(rows);
+    for i in 0..rows {
+        strategy.push(tableau[rows][columns + i].checked_div(total)?);
+    }
+    let value = Ratio::ONE
+        .checked_div(total)?
+        .checked_sub(Ratio::from_integer(shift))?;
+    return Ok((strategy, value));
+}
+
+fn pivot(tableau: &mut [Vec<Ratio>], row: usize, column: usize) -> Result<(), MathError> {
+    let coefficient = tableau[row][column];
+    for value in tableau[row].iter_mut() {
+        *value = value.checked_div(coefficient)?;
+    }
+    let pivot_row = tableau[row].clone();
+    for (i, other) in tableau.iter_mut().enumerate() {
+        let factor = other[column];
+        if i == row || factor == Ratio::ZERO {
+            continue;
+        }
+        for (value, pivot_value) in other.iter_mut().zip(&pivot_row) {
+            *value = value.checked_sub(factor.checked_mul(*pivot_value)?)?;
+        }
+    }
+    return Ok(());
+}
+
+fn approximate(ratio: &Ratio) -> String {
+    return if ratio.denominator() == 1 {
+        ratio.to_string()
+    } else {
+        format!("{} ({:.3})", ratio, ratio.to_f64())
+    };
+}
+
+impl Display for Solution {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "opponent shapes in the guide:")?;
+        for (i, name) in self.names.iter().enumerate() {
+            writeln!(
+                f,
+                "  {}: {} rounds, {:.1}%",
+                name,
+                self.counts[i],
+                self.distribution[i].to_f64() * 100.0
+            )?;
+        }
+        writeln!(f, "expected score per round of always playing:")?;
+        for (i, name) in self.names.iter().enumerate() {
+            writeln!(f, "  {}: {}", name, approximate(&self.expected[i]))?;
+        }
+        writeln!(
+            f,
+            "best response: always {}, {} per round",
+            self.names[self.best_response.0],
+            approximate(&self.expected[self.best_response.0])
+        )?;
+        writeln!(f, "Nash equilibrium strategy:")?;
+        for (i, name) in self.names.iter().enumerate() {
+            writeln!(f, "  {}: {}", name, approximate(&self.nash[i]))?;
+        }
+        writeln!(
+            f,
+            "it guarantees {} per round, and scores {} against the guide's opponent",
+            approximate(&self.value),
+            approximate(&self.nash_expected)
+        )?;
+        return Ok(());
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{maximin, payoffs, solve};
+    use crate::common::ratio::Ratio;
+    use crate::day02::{
+        rock_paper_scissors::{Game, PRESETS},
+        scoring::{ScoringRules, PRESETS as SCORING_PRESETS},
+    };
+
+    fn ratio(numerator: i128, denominator: i128) -> Ratio {
+        return Ratio::new(numerator, denominator).unwrap();
+    }
+
+    #[test]
+    fn best_response() {
+        let game = Game::classic();
+        let rules = ScoringRules::new(&game);
+        let shape = |name: &str| game.shape(name).unwrap();
+        // the example guide's opponent: rock, paper, scissors
+        let opponent = ["Rock", "Paper", "Scissors"].map(shape);
+        let solution = solve(&game, &rules, &opponent).unwrap();
+
+        assert_eq!(solution.distribution, vec![ratio(1, 3); 3]);
+        // rock (4 + 1 + 7) / 3, scissors (3 + 9 + 6) / 3, paper (8 + 5 + 2) / 3
+        let expected: Vec<Ratio> = [4, 6, 5].map(Ratio::from_integer).to_vec();
+        assert_eq!(solution.expected, expected);
+        assert_eq!(solution.best_response, shape("Scissors"));
+        assert!(solve(&game, &rules, &[]).is_err());
+    }
+
+    #[test]
+    fn equilibrium_strategies() {
+        let game = Game::classic();
+        let rules = ScoringRules::parse(&game, SCORING_PRESETS[1].1).unwrap();
+
+        let (strategy, value) = maximin(&payoffs(&game, &rules)).unwrap();
+
+        assert_eq!(strategy, vec![ratio(1, 3); 3]);
+        assert_eq!(value, Ratio::ZERO);
+
+        // losing with rock beats anything else
+        let rules = ScoringRules::parse(&game, "shape Rock 20").unwrap();
+        let (strategy, value) = maximin(&payoffs(&game, &rules)).unwrap();
+        assert_eq!(strategy, vec![Ratio::ONE, Ratio::ZERO, Ratio::ZERO]);
+        assert_eq!(value, Ratio::from_integer(20));
+    }
+
+    #[test]
+    fn nash_strategy_guarantees_the_value() {
+        for (name, description) in PRESETS {
+            let game = Game::parse(description).unwrap();
+            let payoffs = payoffs(&game, &ScoringRules::new(&game));
+
+            let (strategy, value) = maximin(&payoffs).unwrap();
+
+            let total = strategy
+                .iter()
+                .try_fold(Ratio::ZERO, |sum, chance| sum.checked_add(*chance))
+                .unwrap();
+            assert_eq!(total, Ratio::ONE, "{}", name);
+            assert!(strategy.iter().all(|chance| !chance.is_negative()));
+            // no opponent shape holds me below the value, and one holds me to it
+            let mut worst: Option<Ratio> = None;
+            for other in 0..payoffs.len() {
+                let mut expected = Ratio::ZERO;
+                for (row, chance) in payoffs.iter().zip(&strategy) {
+                    let points = Ratio::from_integer(row[other]);
+                    expected = expected
+                        .checked_add(points.checked_mul(*chance).unwrap())
+                        .unwrap();
+                }
+                worst = Some(worst.map_or(expected, |worst| worst.min(expected)));
+            }
+            assert_eq!(worst, Some(value), "{}", name);
+            // and the opponent can hold every strategy to it
+            let transposed: Vec<Vec<i128>> = (0..payoffs.len())
+                .map(|other| payoffs.iter().map(|row| -row[other]).collect())
+                .collect();
+            let (_, opponent_value) = maximin(&transposed).unwrap();
+            assert_eq!(
+                opponent_value,
+                Ratio::ZERO.checked_sub(value).unwrap(),
+                "{}",
+                name
+            );
+        }
+    }
+}
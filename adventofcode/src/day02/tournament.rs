@@ -0,0 +1,351 @@
+//! Round-robin matches between strategy bots, to see how exploitable a guide is.
+
+use std::fmt::Display;
+
+use crate::{
+    common::rng::Rng,
+    day02::{
+        rock_paper_scissors::{Game, RoundOutcome, RoundShapes, Shape},
+        scoring::{Scorer, ScoringRules},
+    },
+};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Strategy {
+    Constant(Shape),
+    /// Every shape in cyclic order.
+    Cyclic,
+    /// Beats the shape the opponent played the most.
+    FrequencyCounter,
+    /// Beats the shape the opponent played the most after their last shape.
+    Markov,
+    /// The shapes of a strategy guide, again from the start once they run out.
+    Guide(Vec<Shape>),
+    Random(u64),
+}
+
+impl Strategy {
+    pub fn name(&self, game: &Game) -> String {
+        return match self {
+            Strategy::Constant(shape) => format!("constant {}", game.rules(*shape).name),
+            Strategy::Cyclic => "cyclic".to_string(),
+            Strategy::FrequencyCounter => "frequency".to_string(),
+            Strategy::Markov => "markov".to_string(),
+            Strategy::Guide(_) => "guide".to_string(),
+            Strategy::Random(_) => "random".to_string(),
+        };
+    }
+}
+
+/// The line-up of `--tournament`, the guide bot playing `guide` if it isn't empty.
+pub fn bots(guide: Vec<Shape>, seed: u64) -> Vec<Strategy> {
+    let mut bots = vec![
+        Strategy::Constant(Shape(0)),
+        Strategy::Cyclic,
+        Strategy::FrequencyCounter,
+        Strategy::Markov,
+        Strategy::Random(seed),
+    ];
+    if !guide.is_empty() {
+        bots.push(Strategy::Guide(guide));
+    }
+    return bots;
+}
+
+/// A strategy with what it has seen of the current match.
+struct Bot<'a> {
+    strategy: &'a Strategy,
+    round: usize,
+    /// Opponent shapes played, and played after each shape.
+    counts: Vec<usize>,
+    transitions: Vec<Vec<usize>>,
+    last_opponent: Option<Shape>,
+    rng: Rng,
+}
+
+impl<'a> Bot<'a> {
+    fn new(game: &Game, strategy: &'a Strategy) -> Self {
+        let shape_count = game.shapes().count();
+        let seed = match strategy {
+            Strategy::Random(seed) => *seed,
+            _ => 0,
+        };
+        return Bot {
+            strategy,
+            round: 0,
+            counts: vec![0; shape_count],
+            transitions: vec![vec![0; shape_count]; shape_count],
+            last_opponent: None,
+            rng: Rng::new(seed),
+        };
+    }
+
+    fn play(&mut self, game: &Game) -> Shape {
+        let shape_count = self.counts.len();
+        return match self.strategy {
+            Strategy::Constant(shape) => *shape,
+            Strategy::Cyclic => Shape(self.round % shape_count),
+            Strategy::FrequencyCounter => beat(game, &self.counts),
+            Strategy::Markov => match self.last_opponent {
+                Some(last) if self.transitions[last.0].iter().any(|&count| count > 0) => {
+                    beat(game, &self.transitions[last.0])
+                }
+                _ => beat(game, &self.counts),
+            },
+            Strategy::Guide(shapes) => shapes[self.round % shapes.len()],
+            Strategy::Random(_) => Shape(self.rng.below(shape_count as u64) as usize),
+        };
+    }
+
+    fn observe(&mut self, opponent: Shape) {
+        self.round += 1;
+        self.counts[opponent.0] += 1;
+        if let Some(last) = self.last_opponent {
+            self.transitions[last.0][opponent.0] += 1;
+        }
+        self.last_opponent = Some(opponent);
+    }
+}
+
+/// The shape beating the most counted one, the first one in cyclic order on ties.
+fn beat(game: &Game, counts: &[usize]) -> Shape {
+    let mut predicted = Shape(0);
+    for (shape, &count) in counts.iter().enumerate() {
+        if count > counts[predicted.0] {
+            predicted = Shape(shape);
+        }
+    }
+    return game.shape_for(&RoundOutcome::Win, predicted);
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Record {
+    pub wins: usize,
+    pub draws: usize,
+    pub losses: usize,
+    pub points: i64,
+}
+
+impl Record {
+    fn add(&mut self, outcome: &RoundOutcome, points: i64) {
+        match outcome {
+            RoundOutcome::Win => self.wins += 1,
+            RoundOutcome::Draw => self.draws += 1,
+            RoundOutcome::Lose => self.losses += 1,
+        }
+        self.points += points;
+    }
+
+    fn merge(&mut self, other: &Record) {
+        self.wins += other.wins;
+        self.draws += other.draws;
+        self.losses += other.losses;
+        self.points += other.points;
+    }
+
+    pub fn rounds(&self) -> usize {
+        return self.wins + self.draws + self.losses;
+    }
+
+    pub fn win_rate(&self) -> f64 {
+        return self.wins as f64 / self.rounds().max(1) as f64;
+    }
+
+    pub fn average_score(&self) -> f64 {
+        return self.points as f64 / self.rounds().max(1) as f64;
+    }
+}
+
+pub struct Match {
+    pub first: usize,
+    pub second: usize,
+    /// From the first bot's side.
+    pub record: Record,
+}
+
+pub struct Tournament {
+    pub names: Vec<String>,
+    pub matches: Vec<Match>,
+    pub standings: Vec<Record>,
+}
+
+/// Plays `rounds` rounds between each pair of bots.
+pub fn round_robin(
+    game: &Game,
+    rules: &ScoringRules,
+    strategies: &[Strategy],
+    rounds: usize,
+) -> Tournament {
+    let mut tournament = Tournament {
+        names: strategies
+            .iter()
+            .map(|strategy| strategy.name(game))
+            .collect(),
+        matches: Vec::new(),
+        standings: vec![Record::default(); strategies.len()],
+    };
+
+    for first in 0..strategies.len() {
+        for second in first + 1..strategies.len() {
+            let (record, other) =
+                play_match(game, rules, &strategies[first], &strategies[second], rounds);
+            tournament.standings[first].merge(&record);
+            tournament.standings[second].merge(&other);
+            tournament.matches.push(Match {
+                first,
+                second,
+                record,
+            });
+        }
+    }
+    return tournament;
+}
+
+/// The records of both bots.
+fn play_match(
+    game: &Game,
+    rules: &ScoringRules,
+    first: &Strategy,
+    second: &Strategy,
+    rounds: usize,
+) -> (Record, Record) {
+    let (mut first, mut second) = (Bot::new(game, first), Bot::new(game, second));
+    let (mut first_scorer, mut second_scorer) =
+        (Scorer::new(game, rules), Scorer::new(game, rules));
+    let (mut first_record, mut second_record) = (Record::default(), Record::default());
+
+    for _ in 0..rounds {
+        let (a, b) = (first.play(game), second.play(game));
+        let score = first_scorer.score(&RoundShapes {
+            opponent_shape: b,
+            my_shape: a,
+        });
+        first_record.add(&score.outcome, score.total());
+        let score = second_scorer.score(&RoundShapes {
+            opponent_shape: a,
+            my_shape: b,
+        });
+        second_record.add(&score.outcome, score.total());
+        first.observe(b);
+        second.observe(a);
+    }
+    return (first_record, second_record);
+}
+
+impl Display for Tournament {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let width = self.names.iter().map(|name| name.len()).max().unwrap_or(0);
+
+        writeln!(f, "matches (wins-draws-losses of the first bot):")?;
+        for game_match in &self.matches {
+            let record = &game_match.record;
+            writeln!(
+                f,
+                "  {:>width$} vs {:<width$}  {}-{}-{}",
+                self.names[game_match.first],
+                self.names[game_match.second],
+                record.wins,
+                record.draws,
+                record.losses,
+                width = width
+            )?;
+        }
+
+        let mut order: Vec<usize> = (0..self.names.len()).collect();
+        order.sort_by(|&a, &b| {
+            let (a, b) = (&self.standings[a], &self.standings[b]);
+            b.average_score().total_cmp(&a.average_score())
+        });
+        writeln!(f, "standings:")?;
+        for bot in order {
+            let record = &self.standings[bot];
+            writeln!(
+                f,
+                "  {:<width$}  win rate {:>5.1}%, average score {:.3}",
+                self.names[bot],
+                record.win_rate() * 100.0,
+                record.average_score(),
+                width = width
+            )?;
+        }
+        return Ok(());
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{bots, round_robin, Record, Strategy};
+    use crate::day02::{rock_paper_scissors::Game, scoring::ScoringRules};
+
+    fn record(first: &Strategy, second: &Strategy, rounds: usize) -> Record {
+        let game = Game::classic();
+        let rules = ScoringRules::new(&game);
+        let tournament = round_robin(&game, &rules, &[first.clone(), second.clone()], rounds);
+        return tournament.matches[0].record.clone();
+    }
+
+    #[test]
+    fn constant_bots() {
+        let game = Game::classic();
+        let shape = |name: &str| game.shape(name).unwrap();
+
+        let record = record(
+            &Strategy::Constant(shape("Rock")),
+            &Strategy::Constant(shape("Scissors")),
+            10,
+        );
+        assert_eq!((record.wins, record.draws, record.losses), (10, 0, 0));
+        assert_eq!(record.points, 10 * (6 + 1));
+        assert_eq!(record.win_rate(), 1.0);
+    }
+
+    #[test]
+    fn counters_exploit_constant_play() {
+        let game = Game::classic();
+        let shape = |name: &str| game.shape(name).unwrap();
+
+        let frequency = record(
+            &Strategy::FrequencyCounter,
+            &Strategy::Constant(shape("Paper")),
+            100,
+        );
+        // the first round is played blind
+        assert_eq!(frequency.wins, 99);
+
+        let markov = record(&Strategy::Markov, &Strategy::Cyclic, 100);
+        // rock, scissors, paper: after seeing each transition once it always wins
+        assert!(markov.wins >= 95, "{:?}", markov);
+
+        let guide = Strategy::Guide(["Rock", "Rock", "Paper"].map(shape).to_vec());
+        let markov = record(&Strategy::Markov, &guide, 300);
+        assert!(markov.wins > markov.losses * 2, "{:?}", markov);
+    }
+
+    #[test]
+    fn random_is_seeded() {
+        let first = record(&Strategy::Random(4), &Strategy::Cyclic, 300);
+        let second = record(&Strategy::Random(4), &Strategy::Cyclic, 300);
+        assert_eq!(first, second);
+        assert_eq!(first.rounds(), 300);
+        assert!(first.wins > 50 && first.losses > 50 && first.draws > 50);
+    }
+
+    #[test]
+    fn standings() {
+        let game = Game::classic();
+        let rules = ScoringRules::new(&game);
+        let shape = |name: &str| game.shape(name).unwrap();
+        let strategies = bots(["Paper", "Paper", "Rock"].map(shape).to_vec(), 1);
+
+        let tournament = round_robin(&game, &rules, &strategies, 50);
+
+        assert_eq!(tournament.matches.len(), 6 * 5 / 2);
+        assert!(tournament
+            .standings
+            .iter()
+            .all(|record| record.rounds() == 5 * 50));
+        let out = tournament.to_string();
+        assert!(out.contains(" constant Rock vs cyclic     "), "{}", out);
+        assert_eq!(out.lines().count(), 1 + 15 + 1 + 6);
+    }
+}
@@ -0,0 +1,263 @@
+//! `--audit`: badge candidates of every group and what the elves should repack.
+
+use std::fmt::Display;
+
+use crate::{
+    common::top_k::TopK,
+    day03::{
+        item_set::{priority, ItemSet},
+        rucksack::{compartments, numbered_rucksacks, rucksack, Layout, RucksackError},
+    },
+};
+
+/// Item types shown in the misplaced summary.
+const MOST_MISPLACED: usize = 5;
+
+pub struct Audit {
+    /// First and last line of each group, and the items of all its rucksacks
+    /// unless one can't be read.
+    pub badges: Vec<(usize, usize, Option<ItemSet>)>,
+    /// Groups without exactly one badge candidate, incomplete or unreadable.
+    pub group_problems: Vec<RucksackError>,
+    /// Rucksacks that can't be read or without exactly one item type in every
+    /// compartment.
+    pub rucksack_problems: Vec<RucksackError>,
+    /// Rucksacks with each item type in every compartment, by priority - 1.
+    pub misplaced: Vec<usize>,
+}
+
+/// Goes through every rucksack and group, unlike the parts which stop at the
+/// first error.
+pub fn audit<'a>(input: impl Iterator<Item = &'a str>, layout: &Layout) -> Audit {
+    let lines: Vec<(usize, &str)> = numbered_rucksacks(input).collect();
+    let mut audit = Audit {
+        badges: Vec::new(),
+        group_problems: Vec::new(),
+        rucksack_problems: Vec::new(),
+        misplaced: vec![0; ItemSet::ALL.len()],
+    };
+
+    for &(line_number, line) in &lines {
+        let compartments = match compartments(line_number, line, layout.compartments) {
+            Ok(compartments) => compartments,
+            Err(err) => {
+                audit.rucksack_problems.push(err);
+                continue;
+            }
+        };
+        let common = compartments
+            .into_iter()
+            .fold(ItemSet::ALL, |common, compartment| common & compartment);
+        for item in common.iter() {
+            audit.misplaced[priority(item).expect("items are letters") - 1] += 1;
+        }
+        if common.len() != 1 {
+            audit
+                .rucksack_problems
+                .push(RucksackError::CommonItems(line_number, common));
+        }
+    }
+
+    for group in lines.chunks(layout.group_size) {
+        let (first, last) = (group[0].0, group[group.len() - 1].0);
+        // unreadable rucksacks are already reported on their own
+        let badges = group
+            .iter()
+            .map(|(line_number, line)| rucksack(*line_number, line).ok())
+            .try_fold(ItemSet::ALL, |common, items| Some(common & items?));
+        audit.badges.push((first, last, badges));
+        let Some(badges) = badges else {
+            audit
+                .group_problems
+                .push(RucksackError::UnreadableGroup(first, last));
+            continue;
+        };
+        if group.len() < layout.group_size {
+            audit.group_problems.push(RucksackError::IncompleteGroup(
+                first,
+                group.len(),
+                layout.group_size,
+            ));
+        } else if badges.len() != 1 {
+            audit
+                .group_problems
+                .push(RucksackError::Badges(first, last, badges));
+        }
+    }
+
+    return audit;
+}
+
+impl Audit {
+    /// The most misplaced item types with their count, by priority on ties.
+    pub fn most_misplaced(&self, count: usize) -> Vec<(u8, usize)> {
+        let mut top = TopK::new(count);
+        for (item, &misplaced) in ItemSet::ALL.iter().zip(&self.misplaced) {
+            if misplaced > 0 {
+                top.push(misplaced, item);
+            }
+        }
+        return top
+            .into_sorted_vec()
+            .into_iter()
+            .map(|(misplaced, item)| (item, misplaced))
+            .collect();
+    }
+}
+
+impl Display for Audit {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "badge candidates per group:")?;
+        for (first, last, badges) in &self.badges {
+            match badges {
+                Some(badges) if badges.is_empty() => {
+                    writeln!(f, "  lines {} to {}: none", first, last)?
+                }
+                Some(badges) => writeln!(f, "  lines {} to {}: {}", first, last, badges)?,
+                None => writeln!(f, "  lines {} to {}: unreadable", first, last)?,
+            }
+        }
+
+        for (title, problems) in [
+            ("groups to fix", &self.group_problems),
+            ("rucksacks to fix", &self.rucksack_problems),
+        ] {
+            if problems.is_empty() {
+                writeln!(f, "{}: none", title)?;
+                continue;
+            }
+            writeln!(f, "{}: {}", title, problems.len())?;
+            for problem in problems {
+                writeln!(f, "  {}", problem)?;
+            }
+        }
+
+        writeln!(f, "most misplaced item types:")?;
+        for (item, misplaced) in self.most_misplaced(MOST_MISPLACED) {
+            writeln!(f, "  {}: {} rucksacks", item as char, misplaced)?;
+        }
+        return Ok(());
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::audit;
+    use crate::day03::{item_set::ItemSet, rucksack::Layout};
+
+    #[test]
+    fn clean_input() {
+        let input = "\
+vJrwpWtwJgWrhcsFMMfFFhFp
+jqHRNqRjqzjGDLGLrsFMfFZSrLrFZsSL
+PmmdzqPrVvPwwTWBwg
+wMqvLMZHhHMvwLHjbvcjnnSBnvTQFn
+ttgJtRGJQctTZtZT
+CrZsJsPPZsGzwwsLwLmpwMDw
+";
+
+        let audit = audit(input.lines(), &Layout::default());
+
+        assert_eq!(
+            audit.to_string(),
+            "\
+badge candidates per group:
+  lines 1 to 3: r
+  lines 4 to 6: Z
+groups to fix: none
+rucksacks to fix: none
+most misplaced item types:
+  p: 1 rucksacks
+  s: 1 rucksacks
+  t: 1 rucksacks
+  v: 1 rucksacks
+  L: 1 rucksacks
+"
+        );
+    }
+
+    #[test]
+    fn problems_are_flagged() {
+        let input = "\
+abab
+bcbd
+bcad
+b1b1
+
+ab
+ab
+ab
+";
+
+        let audit = audit(input.lines(), &Layout::default());
+
+        let items = |items: &str| ItemSet::from_items(items.as_bytes()).unwrap();
+        assert_eq!(
+            audit.badges,
+            vec![
+                (1, 3, Some(items("b"))),
+                (4, 7, None),
+                (8, 8, Some(items("ab")))
+            ]
+        );
+        assert_eq!(audit.most_misplaced(2), vec![(b'b', 2), (b'a', 1)]);
+        let out = audit.to_string();
+        assert!(
+            out.contains(
+                "\
+groups to fix: 2
+  lines 4 to 7: the group can't be audited, a rucksack can't be read
+  line 8: the last group has 1 rucksacks instead of 3
+rucksacks to fix: 6
+  line 1: 2 item types are in every compartment: ab
+  line 3: no item type is in every compartment
+  line 4: '1' is not an item type
+  line 6: no item type is in every compartment
+  line 7: no item type is in every compartment
+  line 8: no item type is in every compartment
+"
+            ),
+            "{}",
+            out
+        );
+    }
+
+    #[test]
+    fn group_without_badge() {
+        let audit = audit("abca\nabcd\nxyzx\n".lines(), &Layout::default());
+
+        assert_eq!(audit.badges, vec![(1, 3, Some(ItemSet::EMPTY))]);
+        assert!(
+            audit.to_string().starts_with(
+                "\
+badge candidates per group:
+  lines 1 to 3: none
+groups to fix: 1
+  lines 1 to 3: the group has no badge candidate
+"
+            ),
+            "{}",
+            audit
+        );
+    }
+
+    #[test]
+    fn unreadable_group() {
+        let audit = audit("ab1a\nab2a\nab3a\n".lines(), &Layout::default());
+
+        assert_eq!(audit.badges, vec![(1, 3, None)]);
+        assert!(
+            audit.to_string().starts_with(
+                "\
+badge candidates per group:
+  lines 1 to 3: unreadable
+groups to fix: 1
+  lines 1 to 3: the group can't be audited, a rucksack can't be read
+rucksacks to fix: 3
+"
+            ),
+            "{}",
+            audit
+        );
+    }
+}
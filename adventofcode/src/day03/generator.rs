@@ -0,0 +1,89 @@
+//! Random day 3 inputs: groups of three rucksacks sharing exactly one badge,
+//! each with exactly one item type in both compartments.
+
+use crate::common::rng::Rng;
+
+const ITEMS: &[u8] = b"abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ";
+const GROUP_SIZE: usize = 3;
+
+pub fn generate(rng: &mut Rng, group_count: usize) -> String {
+    let mut out = String::new();
+    for _ in 0..group_count {
+        let badge = *rng.choose(ITEMS);
+        // every other item type is left out of one rucksack of the group
+        let excluded_from: Vec<usize> = ITEMS.iter().map(|_| rng.below(GROUP_SIZE as u64) as usize).collect();
+
+        for sack in 0..GROUP_SIZE {
+            let mut allowed: Vec<u8> = ITEMS
+                .iter()
+                .zip(&excluded_from)
+                .filter(|(&item, &excluded)| item == badge || excluded != sack)
+                .map(|(&item, _)| item)
+                .collect();
+            out += &generate_rucksack(rng, &mut allowed, badge);
+            out.push('\n');
+        }
+    }
+    return out;
+}
+
+fn generate_rucksack(rng: &mut Rng, allowed: &mut [u8], badge: u8) -> String {
+    rng.shuffle(allowed);
+    let (shared, rest) = allowed.split_first().expect("allowed items are never empty");
+    // split the remaining item types between the compartments
+    let split = rng.between(1, rest.len() - 1);
+    let (left_items, right_items) = rest.split_at(split);
+
+    let half = rng.between(4, 16);
+    let mut left = vec![*shared];
+    let mut right = vec![*shared];
+    if *shared != badge {
+        if left_items.contains(&badge) {
+            left.push(badge);
+        } else {
+            right.push(badge);
+        }
+    }
+    while left.len() < half {
+        left.push(*rng.choose(left_items));
+    }
+    while right.len() < half {
+        right.push(*rng.choose(right_items));
+    }
+    rng.shuffle(&mut left);
+    rng.shuffle(&mut right);
+
+    left.extend(right);
+    return String::from_utf8(left).expect("items are ascii");
+}
+
+#[cfg(test)]
+mod test {
+    use std::collections::HashSet;
+
+    use crate::common::rng::Rng;
+
+    #[test]
+    fn one_shared_item_per_rucksack_and_group() {
+        let input = super::generate(&mut Rng::new(3), 40);
+        let lines: Vec<&str> = input.lines().collect();
+        assert_eq!(lines.len(), 120);
+
+        for line in &lines {
+            assert_eq!(line.len() % 2, 0);
+            let (left, right) = line.split_at(line.len() / 2);
+            let left: HashSet<u8> = left.bytes().collect();
+            let right: HashSet<u8> = right.bytes().collect();
+            assert_eq!(left.intersection(&right).count(), 1, "{}", line);
+        }
+        for group in lines.chunks(3) {
+            let common: Vec<u8> = group[0]
+                .bytes()
+                .filter(|item| group[1].as_bytes().contains(item) && group[2].as_bytes().contains(item))
+                .collect::<HashSet<u8>>()
+                .into_iter()
+                .collect();
+            assert_eq!(common.len(), 1, "{:?}", group);
+        }
+    }
+}
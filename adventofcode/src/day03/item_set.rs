@@ -0,0 +1,155 @@
+//! Sets of rucksack item types as a 52-bit mask, bit `priority - 1` per type.
+
+use std::{
+    fmt::Display,
+    ops::{BitAnd, BitOr, Sub},
+};
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ItemSet(u64);
+
+impl ItemSet {
+    pub const EMPTY: ItemSet = ItemSet(0);
+    /// Every item type, `a` to `z` then `A` to `Z`.
+    pub const ALL: ItemSet = ItemSet((1 << 52) - 1);
+
+    /// `None` if one of the items isn't an ASCII letter.
+    pub fn from_items(items: &[u8]) -> Option<ItemSet> {
+        let mut set = ItemSet::EMPTY;
+        for &item in items {
+            set.0 |= 1 << (priority(item)? - 1);
+        }
+        return Some(set);
+    }
+
+    pub fn contains(&self, item: u8) -> bool {
+        return priority(item).is_some_and(|priority| self.0 & (1 << (priority - 1)) != 0);
+    }
+
+    pub fn union(self, other: ItemSet) -> ItemSet {
+        return ItemSet(self.0 | other.0);
+    }
+
+    pub fn intersection(self, other: ItemSet) -> ItemSet {
+        return ItemSet(self.0 & other.0);
+    }
+
+    pub fn difference(self, other: ItemSet) -> ItemSet {
+        return ItemSet(self.0 & !other.0);
+    }
+
+    pub fn len(&self) -> usize {
+        return self.0.count_ones() as usize;
+    }
+
+    pub fn is_empty(&self) -> bool {
+        return self.0 == 0;
+    }
+
+    /// The item with the lowest priority.
+    pub fn first(&self) -> Option<u8> {
+        if self.is_empty() {
+            return None;
+        }
+        return Some(item(self.0.trailing_zeros() as usize + 1));
+    }
+
+    /// Items by increasing priority.
+    pub fn iter(&self) -> impl Iterator<Item = u8> {
+        let mut rest = self.0;
+        return std::iter::from_fn(move || {
+            if rest == 0 {
+                return None;
+            }
+            let bit = rest.trailing_zeros() as usize;
+            rest &= rest - 1;
+            return Some(item(bit + 1));
+        });
+    }
+}
+
+impl BitAnd for ItemSet {
+    type Output = ItemSet;
+
+    fn bitand(self, other: ItemSet) -> ItemSet {
+        return self.intersection(other);
+    }
+}
+
+impl BitOr for ItemSet {
+    type Output = ItemSet;
+
+    fn bitor(self, other: ItemSet) -> ItemSet {
+        return self.union(other);
+    }
+}
+
+impl Sub for ItemSet {
+    type Output = ItemSet;
+
+    fn sub(self, other: ItemSet) -> ItemSet {
+        return self.difference(other);
+    }
+}
+
+impl Display for ItemSet {
+    /// The items by increasing priority, `abZ`.
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        return write!(
+            f,
+            "{}",
+            String::from_utf8(self.iter().collect()).expect("items are ascii")
+        );
+    }
+}
+
+/// `a` to `z` are 1 to 26, `A` to `Z` 27 to 52.
+pub fn priority(item: u8) -> Option<usize> {
+    return match item {
+        b'a'..=b'z' => Some((item - b'a') as usize + 1),
+        b'A'..=b'Z' => Some((item - b'A') as usize + 27),
+        _ => None,
+    };
+}
+
+/// The item type of a priority from 1 to 52.
+fn item(priority: usize) -> u8 {
+    return if priority <= 26 {
+        b'a' + (priority - 1) as u8
+    } else {
+        b'A' + (priority - 27) as u8
+    };
+}
+
+#[cfg(test)]
+mod test {
+    use super::{priority, ItemSet};
+
+    #[test]
+    fn priorities() {
+        assert_eq!(priority(b'a'), Some(1));
+        assert_eq!(priority(b'z'), Some(26));
+        assert_eq!(priority(b'A'), Some(27));
+        assert_eq!(priority(b'Z'), Some(52));
+        assert_eq!(priority(b'1'), None);
+        let all: Vec<u8> = ItemSet::ALL.iter().collect();
+        assert_eq!(all, b"abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ");
+    }
+
+    #[test]
+    fn set_operations() {
+        let left = ItemSet::from_items(b"vJrwpWtwJgWr").unwrap();
+        let right = ItemSet::from_items(b"hcsFMMfFFhFp").unwrap();
+
+        assert_eq!((left & right).first(), Some(b'p'));
+        assert_eq!((left & right).len(), 1);
+        assert_eq!(left.len(), 8);
+        assert!(left.contains(b'J') && !left.contains(b'j') && !left.contains(b'-'));
+        assert_eq!((left | right).len(), 8 + 7 - 1);
+        assert_eq!((left - right).iter().collect::<Vec<u8>>(), b"grtvwJW");
+        assert_eq!((left - right).to_string(), "grtvwJW");
+        assert_eq!(ItemSet::ALL - ItemSet::ALL, ItemSet::EMPTY);
+        assert_eq!(ItemSet::EMPTY.first(), None);
+        assert_eq!(ItemSet::from_items(b"ab1"), None);
+    }
+}
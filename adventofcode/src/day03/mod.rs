@@ -1,82 +1,110 @@
-use crate::common::config::FileContentConfig;
+mod audit;
+pub mod generator;
+pub mod item_set;
+mod rucksack;
 
-pub fn run(config: FileContentConfig) -> Result<(), &'static str> {
-    let res1 = part1_solve(config.content.lines());
+use crate::common::config::{FileContentConfig, Options};
+use crate::day03::item_set::{priority, ItemSet};
+use crate::day03::rucksack::{compartments, numbered_rucksacks, rucksack, Layout, RucksackError};
+
+pub fn run(config: FileContentConfig) -> Result<(), String> {
+    let layout = Layout::from_options(&config.options)?;
+
+    // before the parts, which stop at the first invalid rucksack
+    if config.options.has("audit") {
+        print!("{}", audit::audit(config.content.lines(), &layout));
+    }
+
+    let res1 = part1_solve(config.content.lines(), layout.compartments)?;
     println!("Part 1 : sum = {:?}", res1);
 
-    let res2 = part2_solve(config.content.lines());
+    let res2 = part2_solve(config.content.lines(), layout.group_size)?;
     println!("Part 2 : sum = {:?}", res2);
 
     return Ok(());
 }
 
-fn part1_solve<'a>(input: impl Iterator<Item = &'a str>) -> usize {
-    let mut sum: usize = 0;
-
-    'outer: for line in input {
-        let split_index = line.len() / 2;
+pub const SOLVER_VERSION: &str = "2";
+/// Options changing the answers.
+pub const ANSWER_OPTIONS: &[&str] = &["group-size", "compartments"];
+/// Options printing more than the answers.
+pub const REPORT_OPTIONS: &[&str] = &["audit"];
+
+pub fn answer(part: u8, input: &str, options: &Options) -> Result<String, String> {
+    let layout = Layout::from_options(options)?;
+    let sum = if part == 1 {
+        part1_solve(input.lines(), layout.compartments)?
+    } else {
+        part2_solve(input.lines(), layout.group_size)?
+    };
+    return Ok(sum.to_string());
+}
 
-        for ch_left in line[..split_index].bytes() {
-            for ch_right in line[split_index..].bytes() {
-                if ch_left == ch_right {
-                    // duplicate item in left and right
-                    sum += get_item_prio(ch_left);
+fn part1_solve<'a>(
+    input: impl Iterator<Item = &'a str>,
+    compartment_count: usize,
+) -> Result<usize, RucksackError> {
+    let mut sum: usize = 0;
 
-                    continue 'outer;
-                }
-            }
+    for (line_number, line) in numbered_rucksacks(input) {
+        // duplicate item in every compartment
+        let common = compartments(line_number, line, compartment_count)?
+            .into_iter()
+            .fold(ItemSet::ALL, |common, compartment| common & compartment);
+        if common.len() != 1 {
+            return Err(RucksackError::CommonItems(line_number, common));
         }
+        sum += priority_of(common);
     }
-    return sum;
+    return Ok(sum);
 }
 
-fn part2_solve<'a>(input: impl Iterator<Item = &'a str>) -> usize {
-    const GROUP_SIZE: usize = 3;
-    let mut group: Vec<&str> = Vec::new();
+fn part2_solve<'a>(
+    input: impl Iterator<Item = &'a str>,
+    group_size: usize,
+) -> Result<usize, RucksackError> {
+    let lines: Vec<(usize, &str)> = numbered_rucksacks(input).collect();
+    let incomplete = lines.len() % group_size;
+    if incomplete > 0 {
+        let first = lines[lines.len() - incomplete].0;
+        return Err(RucksackError::IncompleteGroup(
+            first, incomplete, group_size,
+        ));
+    }
     let mut sum: usize = 0;
 
-    'outer: for line in input {
-        if group.len() < GROUP_SIZE {
-            group.push(line);
-            if group.len() < GROUP_SIZE {
-                continue;
-            }
+    for group in lines.chunks(group_size) {
+        let (first, last) = (group[0].0, group[group.len() - 1].0);
+        // duplicate item in all sacks
+        let mut common = ItemSet::ALL;
+        for (line_number, line) in group {
+            common = common & rucksack(*line_number, line)?;
         }
-
-        for ch1 in group[0].bytes() {
-            for ch2 in group[1].bytes() {
-                if ch1 != ch2 {
-                    continue;
-                }
-                for ch3 in group[2].bytes() {
-                    if ch2 == ch3 {
-                        // duplicate item in all sacks
-                        sum += get_item_prio(ch3);
-
-                        group.clear();
-
-                        continue 'outer;
-                    }
-                }
-            }
+        if common.len() != 1 {
+            return Err(RucksackError::Badges(first, last, common));
         }
-
-        group.clear();
+        sum += priority_of(common);
     }
 
-    return sum;
+    return Ok(sum);
 }
 
-fn get_item_prio(item: u8) -> usize {
-    let upper_bonus = if item.is_ascii_uppercase() { 26 } else { 0 };
-    let ch_prio = upper_bonus + 1 + (item.to_ascii_lowercase() - 'a' as u8);
-    // println!("Found duplicate '{}' prio is {}", ch3 as char, ch_prio);
-
-    return ch_prio as usize;
+/// The priority of the only item of `items`.
+fn priority_of(items: ItemSet) -> usize {
+    return items.first().and_then(priority).expect("one item");
 }
 
 #[cfg(test)]
 mod test {
+    use crate::{
+        common::rng::Rng,
+        day03::{
+            generator::generate,
+            item_set::{priority, ItemSet},
+            rucksack::RucksackError,
+        },
+    };
+
     const INPUT: &str = "\
 vJrwpWtwJgWrhcsFMMfFFhFp
 jqHRNqRjqzjGDLGLrsFMfFZSrLrFZsSL
@@ -90,17 +118,113 @@ CrZsJsPPZsGzwwsLwLmpwMDw
     fn part1_example() {
         let expected = 157;
 
-        let res = super::part1_solve(INPUT.lines());
+        let res = super::part1_solve(INPUT.lines(), 2);
 
-        assert_eq!(res, expected);
+        assert_eq!(res, Ok(expected));
     }
 
     #[test]
     fn part2_example() {
         let expected = 70;
 
-        let res = super::part2_solve(INPUT.lines());
+        let res = super::part2_solve(INPUT.lines(), 3);
+
+        assert_eq!(res, Ok(expected));
+    }
+
+    #[test]
+    fn other_layouts() {
+        // a, then Z
+        assert_eq!(super::part1_solve("abcaBCaxy\nZZZ".lines(), 3), Ok(1 + 52));
+        // b, then d
+        assert_eq!(super::part2_solve("ab\nbc\ncd\nde".lines(), 2), Ok(2 + 4));
+
+        assert_eq!(
+            super::part1_solve("abcaBCaxy\nabcabca".lines(), 3),
+            Err(RucksackError::UnevenCompartments(2, 7, 3))
+        );
+        assert_eq!(
+            super::part2_solve(INPUT.lines(), 4),
+            Err(RucksackError::IncompleteGroup(5, 2, 4))
+        );
+        // blank lines don't count
+        assert_eq!(super::part2_solve("ab\n\nbc\n\n".lines(), 2), Ok(2));
+        assert_eq!(
+            super::part1_solve("\nabc".lines(), 2),
+            Err(RucksackError::UnevenCompartments(2, 3, 2))
+        );
+    }
+
+    #[test]
+    fn invalid_inputs() {
+        let items = |items: &str| ItemSet::from_items(items.as_bytes()).unwrap();
+        let part1 = |input: &str| super::part1_solve(input.lines(), 2).err().unwrap();
+        let part2 = |input: &str| super::part2_solve(input.lines(), 3).err().unwrap();
+
+        assert_eq!(part1("abca\nab1a"), RucksackError::InvalidItem(2, '1'));
+        assert_eq!(
+            part1("abca\nabcd"),
+            RucksackError::CommonItems(2, ItemSet::EMPTY)
+        );
+        assert_eq!(part1("abab"), RucksackError::CommonItems(1, items("ab")));
+        assert_eq!(
+            part2("ab\nbc\nde"),
+            RucksackError::Badges(1, 3, ItemSet::EMPTY)
+        );
+        assert_eq!(
+            part2("abc\n\nbc\ncb"),
+            RucksackError::Badges(1, 4, items("bc"))
+        );
+        assert_eq!(part2("ab\nbc\nb d"), RucksackError::InvalidItem(3, ' '));
+        assert_eq!(
+            part2(&format!("{}abc\nabc\n", INPUT)),
+            RucksackError::IncompleteGroup(7, 2, 3)
+        );
+    }
+
+    /// The nested loops the item sets replaced.
+    fn naive_part1<'a>(input: impl Iterator<Item = &'a str>) -> usize {
+        let mut sum = 0;
+        'outer: for line in input {
+            let (left, right) = line.split_at(line.len() / 2);
+            for ch_left in left.bytes() {
+                if right.bytes().any(|ch_right| ch_right == ch_left) {
+                    sum += priority(ch_left).unwrap();
+                    continue 'outer;
+                }
+            }
+        }
+        return sum;
+    }
 
-        assert_eq!(res, expected);
+    fn naive_part2<'a>(input: impl Iterator<Item = &'a str>) -> usize {
+        let lines: Vec<&str> = input.collect();
+        let mut sum = 0;
+        for group in lines.chunks_exact(3) {
+            let badge = group[0].bytes().find(|ch| {
+                group[1].bytes().any(|other| other == *ch)
+                    && group[2].bytes().any(|other| other == *ch)
+            });
+            sum += badge.and_then(priority).unwrap_or(0);
+        }
+        return sum;
+    }
+
+    #[test]
+    fn same_as_nested_loops() {
+        for seed in 0..20 {
+            let input = generate(&mut Rng::new(seed), 30);
+
+            assert_eq!(
+                super::part1_solve(input.lines(), 2),
+                Ok(naive_part1(input.lines()))
+            );
+            assert_eq!(
+                super::part2_solve(input.lines(), 3),
+                Ok(naive_part2(input.lines()))
+            );
+        }
+        assert_eq!(naive_part1(INPUT.lines()), 157);
+        assert_eq!(naive_part2(INPUT.lines()), 70);
     }
 }
@@ -0,0 +1,215 @@
+//! How rucksacks are laid out: compartments per rucksack, rucksacks per group.
+
+use std::fmt::Display;
+
+use crate::{common::config::Options, day03::item_set::ItemSet};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Layout {
+    pub group_size: usize,
+    pub compartments: usize,
+}
+
+impl Default for Layout {
+    /// Groups of three rucksacks with two compartments, like the puzzle.
+    fn default() -> Self {
+        return Layout {
+            group_size: 3,
+            compartments: 2,
+        };
+    }
+}
+
+impl Layout {
+    /// `--group-size=<n>` and `--compartments=<n>`.
+    pub fn from_options(options: &Options) -> Result<Self, String> {
+        let default = Layout::default();
+        let layout = Layout {
+            group_size: options
+                .parse_value("group-size")?
+                .unwrap_or(default.group_size),
+            compartments: options
+                .parse_value("compartments")?
+                .unwrap_or(default.compartments),
+        };
+        if layout.group_size == 0 || layout.compartments == 0 {
+            return Err("group size and compartment count must be at least 1".to_string());
+        }
+        return Ok(layout);
+    }
+}
+
+/// Rucksacks with their line number, skipping blank lines.
+pub fn numbered_rucksacks<'a>(
+    input: impl Iterator<Item = &'a str>,
+) -> impl Iterator<Item = (usize, &'a str)> {
+    return input
+        .enumerate()
+        .map(|(i, line)| (i + 1, line.trim()))
+        .filter(|(_, line)| !line.is_empty());
+}
+
+/// Every item type of a rucksack.
+pub fn rucksack(line_number: usize, line: &str) -> Result<ItemSet, RucksackError> {
+    return ItemSet::from_items(line.as_bytes()).ok_or_else(|| invalid_item(line_number, line));
+}
+
+/// The item types of each of the `count` equal compartments of a rucksack.
+pub fn compartments(
+    line_number: usize,
+    line: &str,
+    count: usize,
+) -> Result<Vec<ItemSet>, RucksackError> {
+    rucksack(line_number, line)?;
+    if !line.len().is_multiple_of(count) {
+        return Err(RucksackError::UnevenCompartments(
+            line_number,
+            line.len(),
+            count,
+        ));
+    }
+    return Ok(line
+        .as_bytes()
+        .chunks(line.len() / count)
+        .map(|items| ItemSet::from_items(items).expect("items were checked"))
+        .collect());
+}
+
+fn invalid_item(line_number: usize, line: &str) -> RucksackError {
+    let item = line
+        .chars()
+        .find(|item| !item.is_ascii_alphabetic())
+        .expect("a rucksack with an invalid item");
+    return RucksackError::InvalidItem(line_number, item);
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum RucksackError {
+    InvalidItem(usize, char),                // line number, item
+    UnevenCompartments(usize, usize, usize), // line number, items, compartments
+    /// Not exactly one item type in every compartment.
+    CommonItems(usize, ItemSet), // line number, items in every compartment
+    /// Not exactly one badge candidate.
+    Badges(usize, usize, ItemSet), // first and last line of the group, items in every rucksack
+    IncompleteGroup(usize, usize, usize),    // first line, rucksacks, group size
+    /// A rucksack of the group can't be read, so neither can its badge.
+    UnreadableGroup(usize, usize), // first and last line of the group
+}
+
+impl From<RucksackError> for String {
+    fn from(value: RucksackError) -> Self {
+        return value.to_string();
+    }
+}
+
+impl Display for RucksackError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        return match self {
+            RucksackError::InvalidItem(line, item) => {
+                write!(f, "line {}: '{}' is not an item type", line, item)
+            }
+            RucksackError::UnevenCompartments(line, items, compartments) => write!(
+                f,
+                "line {}: {} items can't fill {} equal compartments",
+                line, items, compartments
+            ),
+            RucksackError::CommonItems(line, items) if items.is_empty() => {
+                write!(f, "line {}: no item type is in every compartment", line)
+            }
+            RucksackError::CommonItems(line, items) => write!(
+                f,
+                "line {}: {} item types are in every compartment: {}",
+                line,
+                items.len(),
+                items
+            ),
+            RucksackError::Badges(first, last, items) if items.is_empty() => write!(
+                f,
+                "lines {} to {}: the group has no badge candidate",
+                first, last
+            ),
+            RucksackError::Badges(first, last, items) => write!(
+                f,
+                "lines {} to {}: the group has {} badge candidates: {}",
+                first,
+                last,
+                items.len(),
+                items
+            ),
+            RucksackError::IncompleteGroup(first, rucksacks, group_size) => write!(
+                f,
+                "line {}: the last group has {} rucksacks instead of {}",
+                first, rucksacks, group_size
+            ),
+            RucksackError::UnreadableGroup(first, last) => write!(
+                f,
+                "lines {} to {}: the group can't be audited, a rucksack can't be read",
+                first, last
+            ),
+        };
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{compartments, RucksackError};
+    use crate::day03::item_set::ItemSet;
+
+    #[test]
+    fn split_compartments() {
+        let sets = compartments(1, "abcaBC", 3).unwrap();
+        let expected: Vec<ItemSet> = ["ab", "ca", "BC"]
+            .map(|items| ItemSet::from_items(items.as_bytes()).unwrap())
+            .to_vec();
+        assert_eq!(sets, expected);
+
+        assert_eq!(
+            compartments(4, "abcaB", 2),
+            Err(RucksackError::UnevenCompartments(4, 5, 2))
+        );
+        assert_eq!(
+            RucksackError::UnevenCompartments(4, 5, 2).to_string(),
+            "line 4: 5 items can't fill 2 equal compartments"
+        );
+        // checked before the length
+        assert_eq!(
+            compartments(2, "ab-", 2),
+            Err(RucksackError::InvalidItem(2, '-'))
+        );
+        assert_eq!(
+            compartments(2, "abé", 2).err().unwrap().to_string(),
+            "line 2: 'é' is not an item type"
+        );
+    }
+
+    #[test]
+    fn error_messages() {
+        let items = ItemSet::from_items(b"aB").unwrap();
+        let error = |err: RucksackError| err.to_string();
+
+        assert_eq!(
+            error(RucksackError::CommonItems(3, ItemSet::EMPTY)),
+            "line 3: no item type is in every compartment"
+        );
+        assert_eq!(
+            error(RucksackError::CommonItems(3, items)),
+            "line 3: 2 item types are in every compartment: aB"
+        );
+        assert_eq!(
+            error(RucksackError::Badges(4, 6, ItemSet::EMPTY)),
+            "lines 4 to 6: the group has no badge candidate"
+        );
+        assert_eq!(
+            error(RucksackError::Badges(4, 6, items)),
+            "lines 4 to 6: the group has 2 badge candidates: aB"
+        );
+        assert_eq!(
+            error(RucksackError::IncompleteGroup(7, 2, 3)),
+            "line 7: the last group has 2 rucksacks instead of 3"
+        );
+        assert_eq!(
+            error(RucksackError::UnreadableGroup(1, 3)),
+            "lines 1 to 3: the group can't be audited, a rucksack can't be read"
+        );
+    }
+}
@@ -0,0 +1,35 @@
+//! Random day 4 inputs: `a-b,c-d` section assignment pairs.
+
+use crate::common::rng::Rng;
+
+const MAX_SECTION: usize = 99;
+
+pub fn generate(rng: &mut Rng, pair_count: usize) -> String {
+    let mut out = String::new();
+    for _ in 0..pair_count {
+        let (a, b) = random_range(rng);
+        let (c, d) = random_range(rng);
+        out += &format!("{}-{},{}-{}\n", a, b, c, d);
+    }
+    return out;
+}
+
+fn random_range(rng: &mut Rng) -> (usize, usize) {
+    let start = rng.between(1, MAX_SECTION);
+    return (start, rng.between(start, MAX_SECTION));
+}
+
+#[cfg(test)]
+mod test {
+    use crate::common::rng::Rng;
+
+    #[test]
+    fn generated_input_solves() {
+        let input = super::generate(&mut Rng::new(4), 200);
+
+        let contained = super::super::part1_solve(&input).unwrap();
+        let overlapping = super::super::part2_solve(&input).unwrap();
+        assert!(contained <= overlapping);
+        assert!(overlapping <= 200);
+    }
+}
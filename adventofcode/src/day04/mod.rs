@@ -1,8 +1,7 @@
+pub mod generator;
 mod lexer;
 
-use std::fmt::Display;
-
-use crate::common::config::FileContentConfig;
+use crate::common::{config::FileContentConfig, interval::Range};
 
 use self::lexer::{Lexer, Token};
 
@@ -15,28 +14,6 @@ pub fn run(config: FileContentConfig) -> Result<(), String> {
     return Ok(());
 }
 
-#[derive(Debug)]
-pub struct Range {
-    min: usize,
-    max: usize,
-}
-
-impl Range {
-    fn intersects(&self, other: &Self) -> bool {
-        return self.min <= other.max && self.max >= other.min;
-    }
-
-    fn contains(&self, other: &Self) -> bool {
-        return self.min <= other.min && self.max >= other.max;
-    }
-}
-
-impl Display for Range {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        return write!(f, "{}-{}", self.min, self.max);
-    }
-}
-
 struct ElfRangesIterator<'a> {
     lexer: Lexer<'a>,
 }
@@ -50,7 +27,7 @@ impl<'a> ElfRangesIterator<'a> {
 }
 
 impl Iterator for ElfRangesIterator<'_> {
-    type Item = Result<[Range; 2], String>;
+    type Item = Result<[Range<usize>; 2], String>;
 
     fn next(&mut self) -> Option<Self::Item> {
         let range1 = match parse_range(&mut self.lexer) {
@@ -81,6 +58,16 @@ impl Iterator for ElfRangesIterator<'_> {
     }
 }
 
+pub const SOLVER_VERSION: &str = "1";
+
+pub fn answer(part: u8, input: &str) -> Result<String, String> {
+    if part == 1 {
+        return Ok(part1_solve(input)?.to_string());
+    } else {
+        return Ok(part2_solve(input)?.to_string());
+    }
+}
+
 fn part1_solve<'a>(input: &str) -> Result<usize, String> {
     let mut contained_count = 0;
 
@@ -153,7 +140,7 @@ fn part2_solve<'a>(input: &str) -> Result<usize, String> {
     return Ok(intersect_count);
 }
 
-fn parse_range(lexer: &mut Lexer) -> Result<Option<Range>, String> {
+fn parse_range(lexer: &mut Lexer) -> Result<Option<Range<usize>>, String> {
     let mut min_token = Token::NewLine;
     while let Token::NewLine = min_token {
         min_token = match lexer.get_next_token() {
@@ -180,14 +167,19 @@ fn parse_range(lexer: &mut Lexer) -> Result<Option<Range>, String> {
         Err(err) => return Err(err.to_string()),
     };
 
-    return Ok(Some(Range {
-        min: min.try_into().unwrap(),
-        max: max.try_into().unwrap(),
-    }));
+    return Ok(Some(Range::closed(
+        min.try_into().unwrap(),
+        max.try_into().unwrap(),
+    )));
 }
 
 #[cfg(test)]
 mod test {
+    use crate::common::{
+        interval::Range,
+        prop::{self, Source},
+    };
+
     const INPUT: &str = "\
 2-4,6-8
 2-3,4-5
@@ -232,4 +224,35 @@ mod test {
 
         assert_eq!(res, expected);
     }
+
+    fn random_range(source: &mut Source) -> Range<usize> {
+        let start = source.between(0, 100);
+        return Range::closed(start, source.between(start, 100));
+    }
+
+    #[test]
+    fn contains_implies_intersects() {
+        prop::check(
+            |source| (random_range(source), random_range(source)),
+            |(range1, range2)| !range1.contains(range2) || range1.intersects(range2),
+        );
+    }
+
+    #[test]
+    fn complete_overlaps_are_partial_overlaps() {
+        prop::check(
+            |source| {
+                let pairs = source.vec(0, 20, |source| {
+                    (random_range(source), random_range(source))
+                });
+                pairs
+                    .iter()
+                    .map(|(r1, r2)| {
+                        format!("{}-{},{}-{}\n", r1.start(), r1.end(), r2.start(), r2.end())
+                    })
+                    .collect::<String>()
+            },
+            |input| super::part1_solve(input).unwrap() <= super::part2_solve(input).unwrap(),
+        );
+    }
 }
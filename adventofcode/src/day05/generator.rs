@@ -0,0 +1,69 @@
+//! Random day 5 inputs: a crate diagram followed by moves that are always
+//! valid for the current stack heights.
+
+use crate::common::rng::Rng;
+
+const MAX_STACKS: usize = 9;
+const MAX_INITIAL_HEIGHT: usize = 8;
+
+pub fn generate(rng: &mut Rng, move_count: usize) -> String {
+    let stack_count = rng.between(2, MAX_STACKS);
+    let mut heights: Vec<usize> = (0..stack_count)
+        .map(|_| rng.between(0, MAX_INITIAL_HEIGHT))
+        .collect();
+    // the diagram needs at least one crate row
+    heights[0] = heights[0].max(1);
+
+    let mut out = String::new();
+    let top = *heights.iter().max().unwrap();
+    for level in (0..top).rev() {
+        let cells: Vec<String> = heights
+            .iter()
+            .map(|&height| {
+                if level < height {
+                    format!("[{}]", (b'A' + rng.below(26) as u8) as char)
+                } else {
+                    "   ".to_string()
+                }
+            })
+            .collect();
+        out += &cells.join(" ");
+        out.push('\n');
+    }
+    let labels: Vec<String> = (1..=stack_count).map(|label| format!(" {} ", label)).collect();
+    out += &labels.join(" ");
+    out += "\n\n";
+
+    for _ in 0..move_count {
+        let non_empty: Vec<usize> = (0..stack_count).filter(|&i| heights[i] > 0).collect();
+        let from = *rng.choose(&non_empty);
+        let mut to = rng.between(0, stack_count - 2);
+        if to >= from {
+            to += 1;
+        }
+        let count = rng.between(1, heights[from].min(MAX_INITIAL_HEIGHT));
+        heights[from] -= count;
+        heights[to] += count;
+        out += &format!("move {} from {} to {}\n", count, from + 1, to + 1);
+    }
+    // the parser expects the move list to end with an empty line
+    out.push('\n');
+    return out;
+}
+
+#[cfg(test)]
+mod test {
+    use crate::common::rng::Rng;
+
+    #[test]
+    fn generated_input_solves() {
+        for seed in 0..20 {
+            let input = super::generate(&mut Rng::new(seed), 100);
+
+            let part1 = super::super::part1_solve(&input).unwrap();
+            let part2 = super::super::part2_solve(&input).unwrap();
+            assert_eq!(part1.len(), part2.len());
+            assert!(!part1.is_empty());
+        }
+    }
+}
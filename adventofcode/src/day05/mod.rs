@@ -1,4 +1,5 @@
 mod config;
+pub mod generator;
 mod lexer;
 mod parser;
 
@@ -17,6 +18,62 @@ pub fn run(config: FileContentConfig) -> Result<(), String> {
     return Ok(());
 }
 
+pub const SOLVER_VERSION: &str = "1";
+
+pub fn answer(part: u8, input: &str) -> Result<String, String> {
+    if part == 1 {
+        return Ok(part1_solve(input)?.to_string());
+    } else {
+        return Ok(part2_solve(input)?.to_string());
+    }
+}
+
+/// The parsed crate stacks, and where the moves of `part` leave them, for
+/// `:show stacks` in the REPL.
+pub fn show_stacks(input: &str, part: Option<u8>) -> Result<String, String> {
+    let (mut stacks, instructions) = match parser::parse_part1(&mut Lexer::new(input)) {
+        Ok(parsed) => parsed,
+        Err(err) => return Err(format!("parser error: {}", err)),
+    };
+
+    let mut res = format!(
+        "{} stacks, {} moves\n{}",
+        stacks.len(),
+        instructions.len(),
+        stacks_to_string(&stacks)
+    );
+    if let Some(part @ (1 | 2)) = part {
+        if part == 1 {
+            execute_instructions_part1(&mut stacks, &instructions);
+        } else {
+            execute_instructions_part2(&mut stacks, &instructions);
+        }
+        res += &format!("\nafter the part {} moves:\n{}", part, stacks_to_string(&stacks));
+    }
+    return Ok(res);
+}
+
+/// Draws the stacks like the puzzle input does.
+fn stacks_to_string(stacks: &[VecDeque<Crate>]) -> String {
+    let height = stacks.iter().map(VecDeque::len).max().unwrap_or(0);
+    let mut res = String::new();
+    for level in 0..height {
+        let cells: Vec<String> = stacks
+            .iter()
+            .map(|stack| match (level + stack.len()).checked_sub(height) {
+                // the front of the deque is the top of the stack
+                Some(index) => format!("[{}]", stack[index].label),
+                None => "   ".to_string(),
+            })
+            .collect();
+        res += cells.join(" ").trim_end();
+        res.push('\n');
+    }
+    let labels: Vec<String> = (1..=stacks.len()).map(|label| format!(" {} ", label)).collect();
+    res += labels.join(" ").trim_end();
+    return res;
+}
+
 fn part1_solve(input: &str) -> Result<String, String> {
     let (mut stacks, instructions) = match parser::parse_part1(&mut Lexer::new(input)) {
         Ok(parsed) => parsed,
@@ -88,7 +145,15 @@ fn execute_instructions_part2(stacks: &mut [VecDeque<Crate>], instructions: &Vec
 
 #[cfg(test)]
 mod test {
-    use crate::day05;
+    use std::collections::VecDeque;
+
+    use crate::{
+        common::prop::{self, Source},
+        day05::{
+            self,
+            config::{Crate, Instruction},
+        },
+    };
 
     const INPUT: &str = "    [D]    
 [N] [C]    
@@ -137,4 +202,44 @@ move 1 from 1 to 2
 
         assert_eq!(expected, result);
     }
+
+    fn random_moves(source: &mut Source) -> (Vec<Vec<char>>, Vec<Instruction>) {
+        let stacks = source.vec(1, 9, |source| {
+            source.vec(0, 8, |source| *source.choose(&['A', 'B', 'C']))
+        });
+        let instructions = source.vec(0, 30, |source| {
+            let count = source.between(0, 10);
+            let from = source.between(0, stacks.len() - 1);
+            Instruction::new(count, from, source.between(0, stacks.len() - 1))
+        });
+        return (stacks, instructions);
+    }
+
+    fn to_stacks(labels: &[Vec<char>]) -> Vec<VecDeque<Crate>> {
+        return labels
+            .iter()
+            .map(|stack| stack.iter().map(|&label| Crate::new(label)).collect())
+            .collect();
+    }
+
+    fn crate_count(stacks: &[VecDeque<Crate>]) -> usize {
+        return stacks.iter().map(VecDeque::len).sum();
+    }
+
+    #[test]
+    fn moves_conserve_crates() {
+        prop::check(random_moves, |(labels, instructions)| {
+            let total: usize = labels.iter().map(Vec::len).sum();
+
+            let mut stacks = to_stacks(labels);
+            day05::execute_instructions_part1(&mut stacks, instructions);
+            let part1_total = crate_count(&stacks);
+
+            let mut stacks = to_stacks(labels);
+            day05::execute_instructions_part2(&mut stacks, instructions);
+            let part2_total = crate_count(&stacks);
+
+            part1_total == total && part2_total == total
+        });
+    }
 }
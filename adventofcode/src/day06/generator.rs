@@ -0,0 +1,38 @@
+//! Random day 6 inputs: a datastream with a start-of-message marker hidden
+//! in noise drawn from too few letters to contain one.
+
+use crate::common::rng::Rng;
+
+const NOISE_LETTERS: &[u8] = b"abcdefgh";
+const MARKER_SIZE: usize = 14;
+
+/// A stream of `length` letters, at least `MARKER_SIZE` long.
+pub fn generate(rng: &mut Rng, length: usize) -> String {
+    let length = length.max(MARKER_SIZE);
+    let mut stream: Vec<u8> = (0..length).map(|_| *rng.choose(NOISE_LETTERS)).collect();
+
+    let mut marker: Vec<u8> = (b'a'..=b'z').collect();
+    rng.shuffle(&mut marker);
+    let position = rng.between(0, length - MARKER_SIZE);
+    stream[position..position + MARKER_SIZE].copy_from_slice(&marker[..MARKER_SIZE]);
+
+    let mut out = String::from_utf8(stream).expect("letters are ascii");
+    out.push('\n');
+    return out;
+}
+
+#[cfg(test)]
+mod test {
+    use crate::common::rng::Rng;
+
+    #[test]
+    fn generated_input_has_markers() {
+        for seed in 0..20 {
+            let input = super::generate(&mut Rng::new(seed), 500);
+
+            assert_eq!(input.trim_end().len(), 500);
+            assert!(super::super::part1_solve(&input).unwrap().is_some());
+            assert!(super::super::part2_solve(&input).unwrap().is_some());
+        }
+    }
+}
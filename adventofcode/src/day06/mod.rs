@@ -1,3 +1,5 @@
+pub mod generator;
+
 use std::collections::VecDeque;
 
 use crate::common::config::FileContentConfig;
@@ -25,6 +27,17 @@ pub fn run(config: FileContentConfig) -> Result<(), String> {
     return Ok(());
 }
 
+pub const SOLVER_VERSION: &str = "1";
+
+pub fn answer(part: u8, input: &str) -> Result<String, String> {
+    let index = if part == 1 {
+        part1_solve(input)?
+    } else {
+        part2_solve(input)?
+    };
+    return Ok(index.map_or("not found".to_string(), |index| index.to_string()));
+}
+
 fn part1_solve(content: &str) -> Result<Option<usize>, String> {
     return solve(content, 4);
 }
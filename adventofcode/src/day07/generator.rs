@@ -0,0 +1,119 @@
+//! Random day 7 inputs: `$ cd`/`$ ls` transcripts exploring a random tree.
+
+use crate::common::rng::Rng;
+
+const MAX_FILES_PER_DIR: usize = 4;
+const MAX_FILE_SIZE: u32 = 300_000;
+/// Keeps the total size below the 70_000_000 disk size assumed by part 2.
+const TOTAL_SIZE_BUDGET: u32 = 60_000_000;
+const EXTENSIONS: &[&str] = &["", ".txt", ".dat", ".log", ".bin"];
+
+struct Dir {
+    depth: usize,
+    children: Vec<usize>,
+    files: Vec<u32>,
+}
+
+/// A tree of `dir_count` directories below `/`, nested at most `max_depth`
+/// levels deep. Without depth there is only the root directory.
+pub fn generate(rng: &mut Rng, dir_count: usize, max_depth: usize) -> String {
+    let mut dirs = vec![Dir { depth: 0, children: Vec::new(), files: Vec::new() }];
+    // directories that may still get children
+    let mut open: Vec<usize> = if max_depth > 0 { vec![0] } else { Vec::new() };
+    for _ in 0..dir_count {
+        if open.is_empty() {
+            break;
+        }
+        let parent = *rng.choose(&open);
+        let depth = dirs[parent].depth + 1;
+        dirs.push(Dir { depth, children: Vec::new(), files: Vec::new() });
+        let index = dirs.len() - 1;
+        dirs[parent].children.push(index);
+        if depth < max_depth {
+            open.push(index);
+        }
+    }
+
+    let file_counts: Vec<usize> = dirs.iter().map(|_| rng.between(1, MAX_FILES_PER_DIR)).collect();
+    let total_files: usize = file_counts.iter().sum();
+    let max_file_size = (TOTAL_SIZE_BUDGET / total_files as u32).clamp(1, MAX_FILE_SIZE);
+    for (dir, count) in dirs.iter_mut().zip(file_counts) {
+        dir.files = (0..count)
+            .map(|_| rng.between(max_file_size as usize / 2 + 1, max_file_size as usize) as u32)
+            .collect();
+    }
+
+    let mut out = String::from("$ cd /\n");
+    write_dir(rng, &dirs, 0, &mut out);
+    return out;
+}
+
+fn write_dir(rng: &mut Rng, dirs: &[Dir], index: usize, out: &mut String) {
+    let dir = &dirs[index];
+    *out += "$ ls\n";
+    for (i, _) in dir.children.iter().enumerate() {
+        *out += &format!("dir {}\n", name(i));
+    }
+    for (i, size) in dir.files.iter().enumerate() {
+        *out += &format!("{} {}{}\n", size, name(i), rng.choose(EXTENSIONS));
+    }
+    for (i, &child) in dir.children.iter().enumerate() {
+        *out += &format!("$ cd {}\n", name(i));
+        write_dir(rng, dirs, child, out);
+        *out += "$ cd ..\n";
+    }
+}
+
+/// `a`, `b`, ..., `z`, `ba`, `bb`, ...: unique alphabetic names per index.
+fn name(mut index: usize) -> String {
+    let mut letters = Vec::new();
+    loop {
+        letters.push(b'a' + (index % 26) as u8);
+        index /= 26;
+        if index == 0 {
+            break;
+        }
+    }
+    letters.reverse();
+    return String::from_utf8(letters).expect("letters are ascii");
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{common::rng::Rng, day07::parser::parse_file_tree};
+
+    #[test]
+    fn generated_input_parses() {
+        let input = super::generate(&mut Rng::new(7), 200, 5);
+        let tree = parse_file_tree(&input).unwrap();
+
+        let (_, root_size) = super::super::part1_solve(&tree).unwrap();
+        assert!(root_size <= super::TOTAL_SIZE_BUDGET);
+        assert!(super::super::part2_solve(&tree, root_size).is_ok());
+    }
+
+    #[test]
+    fn depth_is_bounded() {
+        let input = super::generate(&mut Rng::new(8), 100, 3);
+
+        let mut depth: usize = 0;
+        let mut max_depth = 0;
+        for line in input.lines().skip(1) {
+            match line.strip_prefix("$ cd ") {
+                Some("..") => depth -= 1,
+                Some(_) => depth += 1,
+                None => (),
+            }
+            max_depth = max_depth.max(depth);
+        }
+        assert_eq!(max_depth, 3);
+        assert_eq!(input.matches("$ ls").count(), 101);
+    }
+
+    #[test]
+    fn unique_names() {
+        assert_eq!(super::name(0), "a");
+        assert_eq!(super::name(25), "z");
+        assert_eq!(super::name(26), "ba");
+    }
+}
@@ -1,15 +1,18 @@
 //! https://adventofcode.com/2022/day/7
 
 mod file_tree;
+pub mod generator;
 mod parser;
 
 use crate::common::config::FileContentConfig;
 
 use self::file_tree::FileTree;
+use self::parser::FileTreeParseError;
 
 pub fn run(config: FileContentConfig) -> Result<(), String> {
     println!("Day 7!");
     let tree = parser::parse_file_tree(&config.content)?;
+    println!("file tree is parsed:\n{}", tree);
     let (res1, root_size) = part1_solve(&tree)?;
     println!("  Part 1: sum = {}", res1);
 
@@ -19,9 +22,41 @@ pub fn run(config: FileContentConfig) -> Result<(), String> {
     return Ok(());
 }
 
-fn part1_solve(tree: &FileTree) -> Result<(u32, u32), String> {
-    println!("file tree is parsed:\n{}", tree);
+pub const SOLVER_VERSION: &str = "1";
+
+pub fn answer(part: u8, input: &str) -> Result<String, String> {
+    let tree = parser::parse_file_tree(input)?;
+    let (sum, root_size) = part1_solve(&tree)?;
+    if part == 1 {
+        return Ok(sum.to_string());
+    } else {
+        return Ok(part2_solve(&tree, root_size)?.to_string());
+    }
+}
+
+/// The parsed directory tree, for `:show tree` in the REPL.
+pub fn show_tree(input: &str) -> Result<String, String> {
+    if input.trim().is_empty() {
+        return Err("no day 7 transcript loaded".to_string());
+    }
+    let tree = parser::parse_file_tree(input).map_err(|err| describe_error(&err))?;
+    let (_, root_size) = part1_solve(&tree)?;
+    return Ok(format!("{}\ntotal size: {}", tree, root_size));
+}
 
+/// The parser's errors are only meant for debugging.
+fn describe_error(err: &FileTreeParseError) -> String {
+    return match err {
+        FileTreeParseError::UnexpectedEof => "the transcript ends too early".to_string(),
+        FileTreeParseError::UnexpectedCharacter(ch) => {
+            format!("unexpected {:?} in the transcript", ch)
+        }
+        FileTreeParseError::ChildNotFound(name) => format!("cd into unknown directory {}", name),
+        FileTreeParseError::NotADirectory(name) => format!("cd into file {}", name),
+    };
+}
+
+fn part1_solve(tree: &FileTree) -> Result<(u32, u32), String> {
     let max_size = 100_000;
     let mut small_dirs: Vec<(&str, u32)> = Vec::new();
     let root_size = tree
@@ -60,7 +95,10 @@ fn part2_solve(tree: &FileTree, root_size: u32) -> Result<u32, String> {
 
 #[cfg(test)]
 mod test {
-    use crate::day07::parser;
+    use crate::{
+        common::prop::{self, Source},
+        day07::parser,
+    };
 
     const INPUT: &str = "$ cd /
 $ ls
@@ -103,4 +141,56 @@ $ ls
 
         assert_eq!(res, 24933642);
     }
+
+    #[derive(Debug)]
+    struct Dir {
+        files: Vec<u32>,
+        dirs: Vec<Dir>,
+    }
+
+    impl Dir {
+        fn random(source: &mut Source, depth: usize) -> Dir {
+            let files = source.vec(0, 4, |source| source.between(1, 300_000) as u32);
+            let dirs = match depth {
+                0 => Vec::new(),
+                _ => source.vec(0, 3, |source| Dir::random(source, depth - 1)),
+            };
+            return Dir { files, dirs };
+        }
+
+        fn total_size(&self) -> u32 {
+            return self.files.iter().sum::<u32>()
+                + self.dirs.iter().map(Dir::total_size).sum::<u32>();
+        }
+
+        fn write_transcript(&self, out: &mut String) {
+            *out += "$ ls\n";
+            for i in 0..self.dirs.len() {
+                *out += &format!("dir {}\n", "d".repeat(i + 1));
+            }
+            for (i, size) in self.files.iter().enumerate() {
+                *out += &format!("{} {}.txt\n", size, "f".repeat(i + 1));
+            }
+            for (i, dir) in self.dirs.iter().enumerate() {
+                *out += &format!("$ cd {}\n", "d".repeat(i + 1));
+                dir.write_transcript(out);
+                *out += "$ cd ..\n";
+            }
+        }
+    }
+
+    #[test]
+    fn root_size_is_sum_of_file_sizes() {
+        prop::check(
+            |source| Dir::random(source, 4),
+            |root| {
+                let mut transcript = String::from("$ cd /\n");
+                root.write_transcript(&mut transcript);
+
+                let tree = parser::parse_file_tree(&transcript).unwrap();
+                let root_size = tree.root.find_small_child_directories(0, &mut Vec::new());
+                root_size == root.total_size()
+            },
+        );
+    }
 }
@@ -0,0 +1,30 @@
+//! Random day 8 inputs: forests of tree heights.
+
+use crate::common::rng::Rng;
+
+pub fn generate(rng: &mut Rng, width: usize, height: usize) -> String {
+    let mut out = String::with_capacity((width + 1) * height);
+    for _ in 0..height {
+        for _ in 0..width {
+            out.push((b'0' + rng.below(10) as u8) as char);
+        }
+        out.push('\n');
+    }
+    return out;
+}
+
+#[cfg(test)]
+mod test {
+    use crate::common::rng::Rng;
+
+    #[test]
+    fn generated_input_solves() {
+        let input = super::generate(&mut Rng::new(8), 30, 20);
+
+        let grid = super::super::parse_grid(&input).map_err(String::from).unwrap();
+        assert_eq!((grid[0].len(), grid.len()), (30, 20));
+        // at least the edge trees are visible
+        assert!(super::super::part1_solve(&input, None).unwrap() >= 2 * (30 + 20) - 4);
+        assert!(super::super::part2_solve(&input, None).is_ok());
+    }
+}
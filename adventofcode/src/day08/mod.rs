@@ -1,21 +1,119 @@
 //! https://adventofcode.com/2022/day/8
 
+pub mod generator;
+
 use std::fmt::Display;
 
-use crate::common::config::FileContentConfig;
+use crate::common::{
+    config::FileContentConfig,
+    image::{self, Gradient, Palette, Rgb},
+    viz::{Animation, Color, Frame},
+};
+
+const IMAGE_SCALE: usize = 8;
+pub const SOLVER_VERSION: &str = "1";
+/// Options printing more than the answers.
+pub const REPORT_OPTIONS: &[&str] = &["animate", "dump-frames", "export-image"];
 
 pub fn run(config: FileContentConfig) -> Result<(), String> {
     println!("Day 8!");
+    let mut animation = Animation::from_options(&config.options)?;
 
-    let visible_count = part1_solve(&config.content)?;
+    let visible_count = part1_solve(&config.content, animation.as_mut())?;
     println!("{} visible trees", visible_count);
 
-    let highest_score = part2_solve(&config.content)?;
+    let highest_score = part2_solve(&config.content, animation.as_mut())?;
     println!("highest scenic score: {}", highest_score);
 
+    if let Some(animation) = animation {
+        animation.finish()?;
+    }
+
+    if let Some(path) = config.options.parse_value::<String>("export-image")? {
+        export_image(&config.content, &path)?;
+        println!("height map written to {}", path);
+    }
     Ok(())
 }
 
+/// Writes the height map as a PPM image, visible trees in green and hidden
+/// ones in grey, brighter for taller trees.
+fn export_image(input: &str, path: &str) -> Result<(), String> {
+    let grid = parse_grid(input)?;
+    let visible_grid = find_visible_trees(&grid, None);
+    let cells: Vec<Vec<(u8, bool)>> = grid
+        .iter()
+        .zip(visible_grid.iter())
+        .map(|(heights, visibility)| {
+            heights.iter().copied().zip(visibility.iter().copied()).collect()
+        })
+        .collect();
+
+    let hidden = Gradient {
+        from: Rgb(20, 20, 20),
+        to: Rgb(140, 140, 140),
+        min: 0.0,
+        max: 9.0,
+    };
+    let visible = Gradient {
+        from: Rgb(0, 70, 0),
+        to: Rgb(120, 255, 80),
+        min: 0.0,
+        max: 9.0,
+    };
+    let palette = |(height, is_visible): &(u8, bool)| {
+        if *is_visible {
+            visible.color(height)
+        } else {
+            hidden.color(height)
+        }
+    };
+
+    return image::save_ppm(path, &cells, &palette, IMAGE_SCALE)
+        .map_err(|err| format!("could not write image {}: {}", path, err));
+}
+
+/// Tree heights as digits, coloured by `color_at(row_idx, col_idx)`.
+fn forest_frame<F>(grid: &[Vec<u8>], color_at: F, caption: String) -> Frame
+where
+    F: Fn(usize, usize) -> Color,
+{
+    let mut frame = Frame::new(grid[0].len(), grid.len(), ' ');
+    for (row_idx, row) in grid.iter().enumerate() {
+        for (col_idx, height) in row.iter().enumerate() {
+            let ch = char::from_digit(*height as u32, 10).unwrap_or('?');
+            frame.set(col_idx, row_idx, ch, Some(color_at(row_idx, col_idx)));
+        }
+    }
+    frame.set_caption(caption);
+    return frame;
+}
+
+fn visibility_frame(grid: &[Vec<u8>], visible_grid: &[Vec<bool>], caption: String) -> Frame {
+    let color_at = |row_idx: usize, col_idx: usize| {
+        if visible_grid[row_idx][col_idx] {
+            Color::Green
+        } else {
+            Color::Gray
+        }
+    };
+    return forest_frame(grid, color_at, caption);
+}
+
+/// The forest with its visible trees in green, for `:show forest` in the REPL.
+pub fn show_forest(input: &str) -> Result<String, String> {
+    let grid = parse_grid(input)?;
+    let visible_grid = find_visible_trees(&grid, None);
+    let visible_count = visible_grid.iter().flatten().filter(|visible| **visible).count();
+    let caption = format!(
+        "{}x{} forest, {} visible trees",
+        grid[0].len(),
+        grid.len(),
+        visible_count
+    );
+    return Ok(visibility_frame(&grid, &visible_grid, caption).to_ansi_string());
+}
+
 fn parse_grid(content: &str) -> Result<Vec<Vec<u8>>, ForestParserError> {
     let width = match content.lines().next() {
         Some(line) => line.len(),
@@ -49,10 +147,31 @@ fn parse_grid(content: &str) -> Result<Vec<Vec<u8>>, ForestParserError> {
     Ok(grid)
 }
 
-fn part1_solve(input: &str) -> Result<usize, String> {
+pub fn answer(part: u8, input: &str) -> Result<String, String> {
+    if part == 1 {
+        return Ok(part1_solve(input, None)?.to_string());
+    } else {
+        return Ok(part2_solve(input, None)?.to_string());
+    }
+}
+
+fn part1_solve(input: &str, mut animation: Option<&mut Animation>) -> Result<usize, String> {
     let grid = parse_grid(input)?;
     
     println!("grid is {}x{}", grid[0].len(), grid.len());
+    let visible_grid = find_visible_trees(&grid, animation.as_deref_mut());
+    let visible_count = visible_grid.iter().flatten().filter(|is_visible| **is_visible).count();
+
+    if let Some(animation) = animation {
+        let caption = format!("part 1: {} visible trees", visible_count);
+        animation.push(visibility_frame(&grid, &visible_grid, caption));
+    }
+
+    return Ok(visible_count);
+}
+
+/// Whether each tree can be seen from outside the grid.
+fn find_visible_trees(grid: &[Vec<u8>], mut animation: Option<&mut Animation>) -> Vec<Vec<bool>> {
     let mut visible_grid: Vec<Vec<bool>> = grid.iter().map(|row| vec![false; row.len()]).collect();
     visible_grid[0] = vec![true; visible_grid[0].len()];
     let mut top_max_height = grid[0].clone();
@@ -87,6 +206,11 @@ fn part1_solve(input: &str) -> Result<usize, String> {
                 visible_grid[row_idx][col_idx] = true;
             }
         }
+
+        if let Some(animation) = animation.as_deref_mut() {
+            let caption = format!("part 1: scanned row {} from the top, left and right", row_idx);
+            animation.push(visibility_frame(grid, &visible_grid, caption));
+        }
     }
     
     let last_row = grid.last().expect("grid shouldn't be empty");
@@ -104,27 +228,17 @@ fn part1_solve(input: &str) -> Result<usize, String> {
                 visible_grid[row_idx][col_idx] = true;
             }
         }
-    }
 
-
-    let mut visible_count = 0;
-    for row in visible_grid {
-        for is_visible in row {
-            if is_visible {
-                print!("|");
-                // println!("({}, {}) is visible", );
-                visible_count += 1;
-            } else {
-                print!("_");
-            }
+        if let Some(animation) = animation.as_deref_mut() {
+            let caption = format!("part 1: scanned column {} from the bottom", col_idx);
+            animation.push(visibility_frame(grid, &visible_grid, caption));
         }
-        println!();
     }
 
-    Ok(visible_count)
+    return visible_grid;
 }
 
-fn part2_solve(input: &str) -> Result<usize, String> {
+fn part2_solve(input: &str, mut animation: Option<&mut Animation>) -> Result<usize, String> {
     let grid = parse_grid(input)?;
 
     let mut scenic_score: Vec<Vec<usize>> = grid.iter().map(|row| vec![0; row.len()]).collect();
@@ -168,15 +282,44 @@ fn part2_solve(input: &str) -> Result<usize, String> {
             }
 
             scenic_score[row_idx][col_idx] = visible_left * visible_right * visible_top * visible_bottom;
-            // print!("[{};{};{};{}]{} ", visible_top, visible_bottom, visible_right, visible_left, scenic_score[row_idx][col_idx]);
         }
-        // println!()
+
+        if let Some(animation) = animation.as_deref_mut() {
+            animation.push(scenic_frame(&grid, &scenic_score, row_idx));
+        }
     }
 
     let max = scenic_score.iter().flatten().max().expect("there should be data");
     Ok(*max)
 }
 
+/// Highlights the best tree among the rows scored so far.
+fn scenic_frame(grid: &[Vec<u8>], scenic_score: &[Vec<usize>], last_scored_row: usize) -> Frame {
+    let mut best = (0, 0, 0);
+    for (row_idx, row) in scenic_score.iter().enumerate().take(last_scored_row + 1) {
+        for (col_idx, score) in row.iter().enumerate() {
+            if *score > best.2 {
+                best = (row_idx, col_idx, *score);
+            }
+        }
+    }
+
+    let color_at = |row_idx: usize, col_idx: usize| {
+        if (row_idx, col_idx) == (best.0, best.1) {
+            Color::Red
+        } else if row_idx <= last_scored_row {
+            Color::White
+        } else {
+            Color::Gray
+        }
+    };
+    let caption = format!(
+        "part 2: scored row {}, best scenic score so far is {} at ({}, {})",
+        last_scored_row, best.2, best.0, best.1,
+    );
+    return forest_frame(grid, color_at, caption);
+}
+
 enum ForestParserError {
     NoInput,
     InvalidHeight(char),
@@ -203,6 +346,8 @@ impl Display for ForestParserError {
 
 #[cfg(test)]
 mod test {
+    use crate::common::viz::{Animation, Color};
+
     const EXAMPLE_INPUT: &str = "\
 30373
 25512
@@ -213,7 +358,7 @@ mod test {
 
     #[test]
     fn part1_example() {
-        let res = super::part1_solve(EXAMPLE_INPUT).unwrap();
+        let res = super::part1_solve(EXAMPLE_INPUT, None).unwrap();
 
         assert_eq!(res, 21);
     }
@@ -226,7 +371,7 @@ mod test {
 9999
 9999
 ";
-        assert_eq!(super::part1_solve(input).unwrap(), 13);
+        assert_eq!(super::part1_solve(input, None).unwrap(), 13);
 
         let input = "\
 9999
@@ -234,7 +379,7 @@ mod test {
 9999
 9999
 ";
-        assert_eq!(super::part1_solve(input).unwrap(), 13);
+        assert_eq!(super::part1_solve(input, None).unwrap(), 13);
 
         let input = "\
 9999
@@ -242,7 +387,7 @@ mod test {
 9199
 9299
 ";
-        assert_eq!(super::part1_solve(input).unwrap(), 13);
+        assert_eq!(super::part1_solve(input, None).unwrap(), 13);
 
         let input = "\
 9299
@@ -250,7 +395,7 @@ mod test {
 9399
 9999
 ";
-        assert_eq!(super::part1_solve(input).unwrap(), 13);
+        assert_eq!(super::part1_solve(input, None).unwrap(), 13);
 
         let input = "\
 99999
@@ -259,7 +404,7 @@ mod test {
 91999
 91999
 ";
-        assert_eq!(super::part1_solve(input).unwrap(), 19);
+        assert_eq!(super::part1_solve(input, None).unwrap(), 19);
 
         let input = "\
 99199
@@ -268,13 +413,54 @@ mod test {
 99999
 99999
 ";
-        assert_eq!(super::part1_solve(input).unwrap(), 19);
+        assert_eq!(super::part1_solve(input, None).unwrap(), 19);
     }
 
     #[test]
     fn part2_example() {
-        let res = super::part2_solve(EXAMPLE_INPUT).unwrap();
+        let res = super::part2_solve(EXAMPLE_INPUT, None).unwrap();
 
         assert_eq!(res, 8);
     }
+
+    #[test]
+    fn animation() {
+        let mut animation = Animation::new(30);
+
+        super::part1_solve(EXAMPLE_INPUT, Some(&mut animation)).unwrap();
+        super::part2_solve(EXAMPLE_INPUT, Some(&mut animation)).unwrap();
+
+        // 4 rows and 4 columns scanned, a summary, then 5 scored rows
+        assert_eq!(animation.len(), 4 + 4 + 1 + 5);
+        let visibility = animation.frames()[8].to_string();
+        assert!(visibility.starts_with("30373\n25512\n"));
+        let last = animation.frames().last().unwrap();
+        assert_eq!(last.get(2, 3).unwrap().color, Some(Color::Red));
+        assert!(last.caption().contains("best scenic score so far is 8 at (3, 2)"));
+        let hidden = animation.frames()[8].get(3, 1).unwrap();
+        assert_eq!((hidden.ch, hidden.color), ('1', Some(Color::Gray)));
+    }
+
+    #[test]
+    fn export_image() {
+        let path = std::env::temp_dir().join("adventofcode_day08_export_test.ppm");
+        let path = path.to_str().unwrap();
+
+        super::export_image(EXAMPLE_INPUT, path).unwrap();
+
+        let bytes = std::fs::read(path).unwrap();
+        let _ = std::fs::remove_file(path);
+        let size = 5 * super::IMAGE_SCALE;
+        let header = format!("P6\n{} {}\n255\n", size, size);
+        assert!(bytes.starts_with(header.as_bytes()));
+        assert_eq!(bytes.len(), header.len() + size * size * 3);
+        // the top left tree is visible, the 1 at (1, 3) is hidden
+        let pixel = |x: usize, y: usize| {
+            let offset =
+                header.len() + (y * super::IMAGE_SCALE * size + x * super::IMAGE_SCALE) * 3;
+            (bytes[offset], bytes[offset + 1], bytes[offset + 2])
+        };
+        assert!(pixel(0, 0).1 > pixel(0, 0).0);
+        assert_eq!(pixel(3, 1).0, pixel(3, 1).1);
+    }
 }
@@ -0,0 +1,29 @@
+//! Random day 9 inputs: `R 4` move lists.
+
+use crate::common::rng::Rng;
+
+const MAX_STEPS: usize = 20;
+
+pub fn generate(rng: &mut Rng, move_count: usize) -> String {
+    let mut out = String::new();
+    for _ in 0..move_count {
+        let direction = rng.choose(&['U', 'D', 'L', 'R']);
+        out += &format!("{} {}\n", direction, rng.between(1, MAX_STEPS));
+    }
+    return out;
+}
+
+#[cfg(test)]
+mod test {
+    use crate::common::rng::Rng;
+
+    #[test]
+    fn generated_input_solves() {
+        let input = super::generate(&mut Rng::new(9), 300);
+
+        let part1 = super::super::part1_solve(&input, None).unwrap();
+        let part2 = super::super::part2_solve(&input, None).unwrap();
+        assert!(part1 >= part2);
+        assert!(part2 >= 1);
+    }
+}
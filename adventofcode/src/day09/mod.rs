@@ -1,88 +1,178 @@
 //! https://adventofcode.com/2022/day/9
 
+pub mod generator;
 mod rope;
 
 use std::collections::HashSet;
 
 use crate::{
-    common::config::FileContentConfig,
+    common::{
+        config::FileContentConfig,
+        geom::BoundingBox,
+        image::{Rgb, Svg},
+        viz::{Animation, Color, Frame},
+    },
     day09::rope::{Point, ShortRope, RopeList},
 };
 
-use self::rope::Rope;
+use self::rope::{parse_direction, Rope};
+
+const VIEW_WIDTH: usize = 61;
+const VIEW_HEIGHT: usize = 25;
+pub const SOLVER_VERSION: &str = "1";
+/// Options printing more than the answers.
+pub const REPORT_OPTIONS: &[&str] = &["animate", "dump-frames", "export-image"];
 
 pub fn run(config: FileContentConfig) -> Result<(), String> {
     println!("Day 9!");
-    let res1 = part1_solve(&config.content)?;
+    let mut animation = Animation::from_options(&config.options)?;
+    let res1 = part1_solve(&config.content, animation.as_mut())?;
     println!("  Part 1: position count = {}", res1);
-    let res2 = part2_solve(&config.content)?;
+    let res2 = part2_solve(&config.content, animation.as_mut())?;
     println!("  Part 2: position count = {}", res2);
+
+    if let Some(animation) = animation {
+        animation.finish()?;
+    }
+
+    if let Some(path) = config.options.parse_value::<String>("export-image")? {
+        export_image(&config.content, &path)?;
+        println!("  Trajectories written to {}", path);
+    }
     return Ok(());
 }
 
-fn parse_and_step(input: &str, rope: &mut impl Rope) -> Result<usize, String> {
+/// Where the rope went while following the moves.
+struct Trace {
+    visited: HashSet<Point>,
+    head_path: Vec<Point>,
+}
+
+fn parse_and_step(
+    input: &str,
+    rope: &mut impl Rope,
+    mut animation: Option<&mut Animation>,
+    label: &str,
+) -> Result<Trace, String> {
     let mut positions: HashSet<Point> = HashSet::new();
+    let mut head_path: Vec<Point> = vec![rope.knots()[0]];
 
-    // println!("{}", rope);
     for line in input.lines().take_while(|l| !l.is_empty()) {
         let bytes: Vec<u8> = line.bytes().collect();
-        let direction = bytes
-            .first()
-            .expect("expected direction (U,D,L,R)")
-            .try_into()?;
+        let direction = parse_direction(bytes.first().expect("expected direction (U,D,L,R)"))?;
         let count = bytes
             .iter()
             .skip(2)
             .take_while(|ch| ch.is_ascii_digit())
             .fold(0, |acc, ch| acc * 10 + (ch - b'0'));
-        // println!("\n=== {} {} ===", bytes[0] as char, count);
-        for _ in 0..count {
+        for step in 1..=count {
             rope.step(&direction);
-            positions.insert(rope.tail().clone());
-            // println!("{}", rope);
+            positions.insert(*rope.tail());
+            head_path.push(rope.knots()[0]);
+
+            if let Some(animation) = animation.as_deref_mut() {
+                let mut frame = rope_frame(&rope.knots(), &positions);
+                frame.set_caption(format!(
+                    "{}: {} ({}/{}), {} positions visited",
+                    label, line, step, count, positions.len(),
+                ));
+                animation.push(frame);
+            }
         }
     }
 
-    // println!("{}", positions_to_grid_str(&positions, 30));
-    // println!("visited: {:?}", positions);
-    Ok(positions.len())
+    return Ok(Trace {
+        visited: positions,
+        head_path,
+    });
+}
+
+pub fn answer(part: u8, input: &str) -> Result<String, String> {
+    if part == 1 {
+        return Ok(part1_solve(input, None)?.to_string());
+    } else {
+        return Ok(part2_solve(input, None)?.to_string());
+    }
 }
 
-fn part1_solve(input: &str) -> Result<usize, String> {
+fn part1_solve(input: &str, animation: Option<&mut Animation>) -> Result<usize, String> {
     let mut rope = ShortRope::new();
-    return parse_and_step(input, &mut rope);
+    let trace = parse_and_step(input, &mut rope, animation, "part 1")?;
+    return Ok(trace.visited.len());
 }
 
-fn part2_solve(input: &str) -> Result<usize, String> {
+fn part2_solve(input: &str, animation: Option<&mut Animation>) -> Result<usize, String> {
     let mut rope = RopeList::new(10);
-    let position_count = parse_and_step(input, &mut rope);
-    // println!("{}", rope.to_grid_str(30));
-    return position_count;
+    let trace = parse_and_step(input, &mut rope, animation, "part 2")?;
+    return Ok(trace.visited.len());
+}
+
+/// Writes an SVG with the head trajectory and the positions visited by the
+/// tail of both ropes.
+fn export_image(input: &str, path: &str) -> Result<(), String> {
+    let short = parse_and_step(input, &mut ShortRope::new(), None, "part 1")?;
+    let long = parse_and_step(input, &mut RopeList::new(10), None, "part 2")?;
+
+    let bounds = BoundingBox::from_points(short.head_path.iter().chain(short.visited.iter()))
+        .expect("the head path contains at least the start");
+    let margin = 2.0;
+    let mut svg = Svg::new((
+        bounds.min.x as f64 - margin,
+        bounds.min.y as f64 - margin,
+        bounds.width() as f64 + 2.0 * margin,
+        bounds.height() as f64 + 2.0 * margin,
+    ))
+    .background(Rgb(16, 16, 24));
+
+    svg.points(&to_coords(&short.visited), Rgb(70, 110, 200), 0.45);
+    svg.points(&to_coords(&long.visited), Rgb(240, 180, 40), 0.3);
+    svg.polyline(&to_coords(&short.head_path), Rgb(220, 60, 60), 0.1);
+    svg.points(&[(0.0, 0.0)], Rgb::WHITE, 0.6);
+
+    return svg.save(path)
+        .map_err(|err| format!("could not write image {}: {}", path, err));
 }
 
-fn positions_to_grid_str(positions: &HashSet<Point>, size: usize) -> String {
-    let mut grid_str = String::new();
-    let min_coord = -(size as isize / 2);
-    let max_coord = size as isize / 2;
-    for y in min_coord..=max_coord {
-        for x in min_coord..=max_coord {
-            let point_opt = positions.iter().find(|point| *point == &Point::new(x, y));
-
-            if x == 0 && y == 0 {
-                grid_str += "s";
-            } else if point_opt.is_some() {
-                grid_str += "#";
-            } else {
-                grid_str += ".";
+fn to_coords<'a>(points: impl IntoIterator<Item = &'a Point>) -> Vec<(f64, f64)> {
+    return points.into_iter().map(|p| (p.x as f64, p.y as f64)).collect();
+}
+
+/// The area around the head, with the start `s` and visited positions `#`.
+fn rope_frame(knots: &[Point], visited: &HashSet<Point>) -> Frame {
+    let origin = knots[0] - Point::new(VIEW_WIDTH as isize / 2, VIEW_HEIGHT as isize / 2);
+    let mut frame = Frame::new(VIEW_WIDTH, VIEW_HEIGHT, '.');
+
+    for y in 0..VIEW_HEIGHT {
+        for x in 0..VIEW_WIDTH {
+            let point = origin + Point::new(x as isize, y as isize);
+            if point == Point::default() {
+                frame.set(x, y, 's', Some(Color::Cyan));
+            } else if visited.contains(&point) {
+                frame.set(x, y, '#', Some(Color::Gray));
             }
         }
-        grid_str += "\n";
     }
-    return grid_str;
+
+    // draw from tail to head so the knots closest to the head stay on top
+    for (index, knot) in knots.iter().enumerate().rev() {
+        let (ch, color) = match index {
+            0 => ('H', Color::Red),
+            i if i == knots.len() - 1 && knots.len() == 2 => ('T', Color::Yellow),
+            i => (char::from_digit(i as u32, 10).unwrap_or('+'), Color::Yellow),
+        };
+        let relative = *knot - origin;
+        if relative.x >= 0 && relative.y >= 0 {
+            frame.set(relative.x as usize, relative.y as usize, ch, Some(color));
+        }
+    }
+
+    return frame;
 }
 
 #[cfg(test)]
 mod test {
+    use crate::common::viz::Animation;
+
     const INPUT: &str = "\
 R 4
 U 4
@@ -96,14 +186,14 @@ R 2
 
     #[test]
     fn part1_example() {
-        let res = super::part1_solve(INPUT).unwrap();
+        let res = super::part1_solve(INPUT, None).unwrap();
 
         assert_eq!(res, 13);
     }
 
     #[test]
     fn part2_example() {
-        let res = super::part2_solve(INPUT).unwrap();
+        let res = super::part2_solve(INPUT, None).unwrap();
 
         assert_eq!(res, 1);
     }
@@ -120,8 +210,41 @@ D 10
 L 25
 U 20
 ";
-        let res = super::part2_solve(large_input).unwrap();
+        let res = super::part2_solve(large_input, None).unwrap();
 
         assert_eq!(res, 36);
     }
+
+    #[test]
+    fn part1_animation() {
+        let mut animation = Animation::new(30);
+
+        super::part1_solve(INPUT, Some(&mut animation)).unwrap();
+
+        // one frame per step
+        assert_eq!(animation.len(), 24);
+        let last = animation.frames().last().unwrap();
+        assert_eq!(last.caption(), "part 1: R 2 (2/2), 13 positions visited");
+        let (center_x, center_y) = (super::VIEW_WIDTH / 2, super::VIEW_HEIGHT / 2);
+        assert_eq!(last.get(center_x, center_y).unwrap().ch, 'H');
+        assert_eq!(last.get(center_x - 1, center_y).unwrap().ch, 'T');
+    }
+
+    #[test]
+    fn export_image() {
+        let path = std::env::temp_dir().join("adventofcode_day09_export_test.svg");
+        let path = path.to_str().unwrap();
+
+        super::export_image(INPUT, path).unwrap();
+
+        let svg = std::fs::read_to_string(path).unwrap();
+        let _ = std::fs::remove_file(path);
+        // the head stays within x=0..5 and y=-4..0
+        assert!(svg.starts_with(
+            "<svg xmlns=\"http://www.w3.org/2000/svg\" viewBox=\"-2 -6 10 9\">"
+        ));
+        // 13 positions for part 1, 1 for part 2, and the start
+        assert_eq!(svg.matches("<circle").count(), 13 + 1 + 1);
+        assert!(svg.contains("<polyline points=\"0,0 1,0 2,0 3,0 4,0 4,-1"));
+    }
 }
@@ -1,5 +1,7 @@
 use std::fmt::Display;
 
+use crate::common::geom::{Direction, Point2};
+
 pub struct IllegalCharacter;
 
 impl Display for IllegalCharacter {
@@ -14,58 +16,22 @@ impl From<IllegalCharacter> for String {
     }
 }
 
-pub enum Direction {
-    Up,
-    Left,
-    Down,
-    Right,
-}
-
-impl Display for Direction {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "{}", match self {
-            Direction::Up => "U",
-            Direction::Left => "L",
-            Direction::Down => "D",
-            Direction::Right => "R",
-        })
-    }
-}
-
-impl TryFrom<&u8> for Direction {
-    type Error = IllegalCharacter;
-
-    fn try_from(value: &u8) -> Result<Self, Self::Error> {
-        match value {
-            b'U' => Ok(Direction::Up),
-            b'L' => Ok(Direction::Left),
-            b'D' => Ok(Direction::Down),
-            b'R' => Ok(Direction::Right),
-            _ => Err(IllegalCharacter),
-        }
-    }
+pub fn parse_direction(value: &u8) -> Result<Direction, IllegalCharacter> {
+    return match value {
+        b'U' => Ok(Direction::Up),
+        b'L' => Ok(Direction::Left),
+        b'D' => Ok(Direction::Down),
+        b'R' => Ok(Direction::Right),
+        _ => Err(IllegalCharacter),
+    };
 }
 
-#[derive(PartialEq, Eq, Hash, Clone, Debug)]
-pub struct Point {
-    x: isize,
-    y: isize,
-}
+pub type Point = Point2<isize>;
 
-impl Default for Point {
-    fn default() -> Self {
-        return Point::new(0, 0);
-    }
-}
-
-impl Point {
-    pub fn new(x: isize, y: isize) -> Self {
-        Self { x, y }
-    }
-
-    fn translate(&mut self, offset_x: isize, offset_y: isize) {
-        self.x += offset_x;
-        self.y += offset_y;
+/// Moves `follower` one step towards `leader` if they are no longer touching.
+fn follow(leader: &Point, follower: &mut Point) {
+    if leader.chebyshev_distance(follower) > 1 {
+        *follower += (*leader - *follower).signum();
     }
 }
 
@@ -73,6 +39,9 @@ pub trait Rope {
     fn step(&mut self, direction: &Direction);
 
     fn tail(&self) -> &Point;
+
+    /// Every knot, from head to tail.
+    fn knots(&self) -> Vec<Point>;
 }
 
 /// day 9 part 1
@@ -92,46 +61,17 @@ impl ShortRope {
 
 impl Rope for ShortRope {
     fn step(&mut self, direction: &Direction) {
-        let (offset_x, offset_y) = match direction {
-            Direction::Up => (0, -1),
-            Direction::Left => (-1, 0),
-            Direction::Down => (0, 1),
-            Direction::Right => (1, 0),
-        };
-        self.head.translate(offset_x, offset_y);
-
-        if offset_x != 0 {
-            let tail_diff_x = self.head.x - self.tail.x;
-            match tail_diff_x {
-                2 => {
-                    self.tail.translate(1, 0);
-                    self.tail.y = self.head.y;
-                }
-                -2 => {
-                    self.tail.translate(-1, 0);
-                    self.tail.y = self.head.y;
-                }
-                _ => (),
-            }
-        } else if offset_y != 0 {
-            let tail_diff_y = self.head.y - self.tail.y;
-            match tail_diff_y {
-                2 => {
-                    self.tail.translate(0, 1);
-                    self.tail.x = self.head.x;
-                }
-                -2 => {
-                    self.tail.translate(0, -1);
-                    self.tail.x = self.head.x;
-                }
-                _ => (),
-            }
-        }
+        self.head += direction.offset();
+        follow(&self.head, &mut self.tail);
     }
 
     fn tail(&self) -> &Point {
         &self.tail
     }
+
+    fn knots(&self) -> Vec<Point> {
+        return vec![self.head, self.tail];
+    }
 }
 
 impl Display for ShortRope {
@@ -195,88 +135,17 @@ impl RopeList {
             knots: vec![Point::default(); size],
         };
     }
-
-    pub fn to_grid_str(&self, size: usize) -> String {
-        let mut grid_str = String::new();
-        let min_coord = -(size as isize / 2);
-        let max_coord = size as isize / 2;
-        for y in min_coord..=max_coord {
-            for x in min_coord..=max_coord {
-                let point_opt = self.knots.iter().enumerate().find(|(_, point)| point.x == x && point.y == y);
-                
-                if let Some((index, _)) = point_opt {
-                    grid_str += &index.to_string(); // can be too wide if rope size > 10
-                } else if x == 0 && y == 0 {
-                    grid_str += "s";
-                } else {
-                    grid_str += ".";
-                }
-            }
-            grid_str += "\n";
-        }
-        return grid_str;
-    }
 }
 
 impl Rope for RopeList {
     fn step(&mut self, direction: &Direction) {
-        let (mut offset_x, mut offset_y) = match direction {
-            Direction::Up => (0, -1),
-            Direction::Left => (-1, 0),
-            Direction::Down => (0, 1),
-            Direction::Right => (1, 0),
-        };
+        self.knots[0] += direction.offset();
 
-        let mut leader_opt: Option<&mut Point> = None;
-        for follower in &mut self.knots {
-            if let Some(leader) = leader_opt {
-                leader.translate(offset_x, offset_y);
-                let follower_diff_x = leader.x - follower.x;
-                let follower_diff_y = leader.y - follower.y;
-
-                if offset_x != 0 && offset_y != 0 && (follower_diff_x.abs() == 2 || follower_diff_y.abs() == 2) {
-                    if follower_diff_x == 0 {
-                        offset_x = 0;
-                    }
-                    if follower_diff_y == 0 {
-                        offset_y = 0;
-                    }
-                } else if offset_x != 0 {
-                    offset_y = leader.y - follower.y;
-                    match follower_diff_x {
-                        2 => {
-                            offset_x = 1;
-                        },
-                        -2 => {
-                            offset_x = -1;
-                        },
-                        _ => {
-                            offset_x = 0;
-                            offset_y = 0;
-                        },
-                    }
-                } else if offset_y != 0 {
-                    offset_x = leader.x - follower.x;
-                    match follower_diff_y {
-                        2 => {
-                            offset_y = 1;
-                        },
-                        -2 => {
-                            offset_y = -1;
-                        },
-                        _ => {
-                            offset_x = 0;
-                            offset_y = 0;
-                        },
-                    }
-                }
-                // println!("leader={:?} \tfollower={:?} \tfollower_offset=({}, {})", leader, follower, offset_x, offset_y);
-            }
-            leader_opt = Some(follower);
+        let mut leader = self.knots[0];
+        for follower in self.knots.iter_mut().skip(1) {
+            follow(&leader, follower);
+            leader = *follower;
         }
-        leader_opt.unwrap().translate(offset_x, offset_y);
-
-        // println!("=== {} ===\n{}", direction, self.to_grid_str(30));
     }
 
     fn tail(&self) -> &Point {
@@ -285,4 +154,60 @@ impl Rope for RopeList {
             .last()
             .expect("rope list shouldn't be empty by construction");
     }
+
+    fn knots(&self) -> Vec<Point> {
+        return self.knots.clone();
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{Point, Rope, RopeList, ShortRope};
+    use crate::common::{
+        geom::Direction,
+        prop::{self, Source},
+        search,
+    };
+
+    #[test]
+    fn search_over_points() {
+        let target = Point::new(3, -2);
+
+        let res = search::bfs(
+            [Point::default()],
+            |p: &Point| p.neighbours4(),
+            |p| *p == target,
+        );
+
+        assert_eq!(res.goal_cost(), Some(5));
+        assert_eq!(res.path().unwrap().len(), 6);
+    }
+
+    fn random_moves(source: &mut Source) -> Vec<(Direction, usize)> {
+        return source.vec(0, 30, |source| (*source.choose(&Direction::ALL), source.between(1, 10)));
+    }
+
+    /// Steps through `moves`, checking after every step that each knot is at
+    /// most one cell away from its leader.
+    fn stays_connected(rope: &mut impl Rope, moves: &[(Direction, usize)]) -> bool {
+        for (direction, count) in moves {
+            for _ in 0..*count {
+                rope.step(direction);
+                let knots = rope.knots();
+                if knots.windows(2).any(|pair| pair[0].chebyshev_distance(&pair[1]) > 1) {
+                    return false;
+                }
+            }
+        }
+        return true;
+    }
+
+    #[test]
+    fn tail_never_drifts() {
+        prop::check(random_moves, |moves| stays_connected(&mut ShortRope::new(), moves));
+        prop::check(
+            |source| (source.between(2, 12), random_moves(source)),
+            |(size, moves)| stays_connected(&mut RopeList::new(*size), moves),
+        );
+    }
 }
@@ -8,4 +8,215 @@ pub mod day06;
 pub mod day07;
 pub mod day08;
 pub mod day09;
+pub mod repl;
 
+
+use std::fs;
+
+use common::{
+    cache::{Cache, Key},
+    config::{CacheConfig, FileContentConfig, GenerateConfig, Options},
+    rng::Rng,
+};
+
+/// The day run when no `--day` option is given.
+pub const LATEST_DAY: u8 = 9;
+
+/// Runs the solver for the day selected with `--day=<n>`, through the answer
+/// cache when it is enabled.
+pub fn run(config: FileContentConfig) -> Result<(), String> {
+    let day = config.options.parse_value("day")?.unwrap_or(LATEST_DAY);
+
+    if let Some(cache) = Cache::from_options(&config.options) {
+        // only the answers are cached, reports need the solver to run
+        if !report_options(day).iter().any(|name| config.options.has(name)) {
+            return run_cached(day, &config.content, &config.options, &cache);
+        }
+    }
+
+    return match day {
+        1 => day01::solve(config),
+        2 => day02::solve(config),
+        3 => day03::run(config),
+        4 => day04::run(config),
+        5 => day05::run(config),
+        6 => day06::run(config),
+        7 => day07::run(config),
+        8 => day08::run(config),
+        9 => day09::run(config),
+        _ => Err(format!("no solver for day {}", day)),
+    };
+}
+
+/// Prints the answers of both parts, only solving the ones missing from the
+/// cache.
+fn run_cached(day: u8, input: &str, options: &Options, cache: &Cache) -> Result<(), String> {
+    println!("Day {}!", day);
+    let settings = answer_settings(day, options);
+    for part in 1..=2 {
+        let key = Key::new(day, part, solver_version(day)?, &settings, input);
+        match cache.get(&key) {
+            Some(answer) => println!("  Part {}: {} (cache hit)", part, answer),
+            None => {
+                let answer = answer_with_options(day, part, input, options)?;
+                println!("  Part {}: {}", part, answer);
+                cache
+                    .put(&key, &answer)
+                    .map_err(|err| format!("could not write to cache: {}", err))?;
+            }
+        }
+    }
+    return Ok(());
+}
+
+/// The answer of one part, without the extra output of `run`.
+pub fn answer(day: u8, part: u8, input: &str) -> Result<String, String> {
+    return answer_with_options(day, part, input, &Options::default());
+}
+
+/// The answer of one part, with the options of `answer_options` applied.
+pub fn answer_with_options(
+    day: u8,
+    part: u8,
+    input: &str,
+    options: &Options,
+) -> Result<String, String> {
+    if !(1..=2).contains(&part) {
+        return Err(format!("no part {}", part));
+    }
+
+    return match day {
+        1 => day01::answer(part, input),
+        2 => day02::answer(part, input, options),
+        3 => day03::answer(part, input, options),
+        4 => day04::answer(part, input),
+        5 => day05::answer(part, input),
+        6 => day06::answer(part, input),
+        7 => day07::answer(part, input),
+        8 => day08::answer(part, input),
+        9 => day09::answer(part, input),
+        _ => Err(format!("no solver for day {}", day)),
+    };
+}
+
+/// Part of the cache key, changing it invalidates the cached answers of a day.
+fn solver_version(day: u8) -> Result<&'static str, String> {
+    return match day {
+        1 => Ok(day01::SOLVER_VERSION),
+        2 => Ok(day02::SOLVER_VERSION),
+        3 => Ok(day03::SOLVER_VERSION),
+        4 => Ok(day04::SOLVER_VERSION),
+        5 => Ok(day05::SOLVER_VERSION),
+        6 => Ok(day06::SOLVER_VERSION),
+        7 => Ok(day07::SOLVER_VERSION),
+        8 => Ok(day08::SOLVER_VERSION),
+        9 => Ok(day09::SOLVER_VERSION),
+        _ => Err(format!("no solver for day {}", day)),
+    };
+}
+
+/// Options changing the answers of a day, part of the cache key.
+fn answer_options(day: u8) -> &'static [&'static str] {
+    return match day {
+        2 => day02::ANSWER_OPTIONS,
+        3 => day03::ANSWER_OPTIONS,
+        _ => &[],
+    };
+}
+
+/// Options printing more than the answers, the cache is skipped when one is
+/// given.
+fn report_options(day: u8) -> &'static [&'static str] {
+    return match day {
+        1 => day01::REPORT_OPTIONS,
+        2 => day02::REPORT_OPTIONS,
+        3 => day03::REPORT_OPTIONS,
+        8 => day08::REPORT_OPTIONS,
+        9 => day09::REPORT_OPTIONS,
+        _ => &[],
+    };
+}
+
+/// The answer options given, one `name=value` line each. Values naming a file,
+/// like a game description, are followed by its content so that editing the
+/// file changes the cache key.
+fn answer_settings(day: u8, options: &Options) -> String {
+    let mut settings = String::new();
+    for name in answer_options(day) {
+        if !options.has(name) {
+            continue;
+        }
+        let value = options.get(name).unwrap_or_default();
+        settings += &format!("{}={}\n", name, value);
+        if let Ok(content) = fs::read_to_string(value) {
+            settings += &content;
+        }
+    }
+    return settings;
+}
+
+/// Runs `cache clear`.
+pub fn cache_command(config: CacheConfig) -> Result<(), String> {
+    return match config.action.as_str() {
+        "clear" => {
+            let cache = Cache::dir_from_options(&config.options);
+            let removed = cache
+                .clear()
+                .map_err(|err| format!("could not clear {}: {}", cache.dir().display(), err))?;
+            println!("Removed {} cached answers from {}", removed, cache.dir().display());
+            Ok(())
+        }
+        action => Err(format!("unknown cache action: {}", action)),
+    };
+}
+
+/// Writes a random input for the day given to `generate`, to `--output=<path>`
+/// or standard output.
+pub fn generate(config: GenerateConfig) -> Result<(), String> {
+    let input = generate_input(config.day, &config.options)?;
+
+    return match config.options.get("output") {
+        Some(path) => {
+            fs::write(path, input).map_err(|err| format!("could not write {}: {}", path, err))
+        }
+        None => {
+            print!("{}", input);
+            Ok(())
+        }
+    };
+}
+
+/// Sizes default to roughly the size of the real puzzle inputs, `--size`
+/// replaces the default and `--scale` multiplies it. Only the height of the
+/// day 8 forest is scaled, so that its size grows linearly like the others.
+fn generate_input(day: u8, options: &Options) -> Result<String, String> {
+    let mut rng = Rng::new(options.parse_value("seed")?.unwrap_or(0));
+    let scale: usize = options.parse_value("scale")?.unwrap_or(1);
+    let size = |default: usize| -> Result<usize, String> {
+        Ok(options.parse_value("size")?.unwrap_or(default).saturating_mul(scale))
+    };
+
+    let input = match day {
+        1 => day01::generator::generate(&mut rng, size(250)?),
+        2 => day02::generator::generate(&mut rng, size(2500)?),
+        3 => day03::generator::generate(&mut rng, size(100)?),
+        4 => day04::generator::generate(&mut rng, size(1000)?),
+        5 => day05::generator::generate(&mut rng, size(500)?),
+        6 => day06::generator::generate(&mut rng, size(4096)?),
+        7 => day07::generator::generate(
+            &mut rng,
+            size(175)?,
+            options.parse_value("depth")?.unwrap_or(8),
+        ),
+        8 => {
+            let side: usize = options.parse_value("size")?.unwrap_or(99);
+            let width = options.parse_value("width")?.unwrap_or(side);
+            let height: usize = options.parse_value("height")?.unwrap_or(side);
+            day08::generator::generate(&mut rng, width, height.saturating_mul(scale))
+        }
+        9 => day09::generator::generate(&mut rng, size(2000)?),
+        _ => return Err(format!("no generator for day {}", day)),
+    };
+
+    return Ok(input);
+}
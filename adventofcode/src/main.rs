@@ -1,21 +1,68 @@
-use adventofcode::common::config::FileContentConfig;
+use adventofcode::common::config::{CacheConfig, FileContentConfig, GenerateConfig, ReplConfig};
 
 use std::{env, process};
 
 fn main() {
+    match env::args().nth(1).as_deref() {
+        Some("generate") => return generate(),
+        Some("cache") => return cache(),
+        Some("repl") => return repl(),
+        _ => (),
+    }
+
     let config = FileContentConfig::build(env::args()).unwrap_or_else(|err| {
         handle_parsing_error(err);
         process::exit(1);
     });
 
-    if let Err(err) = adventofcode::day09::run(config) {
+    if let Err(err) = adventofcode::run(config) {
+        handle_app_error(&err);
+        process::exit(1);
+    }
+}
+
+fn generate() {
+    let config = GenerateConfig::build(env::args()).unwrap_or_else(|err| {
+        handle_parsing_error(err);
+        process::exit(1);
+    });
+
+    if let Err(err) = adventofcode::generate(config) {
+        handle_app_error(&err);
+        process::exit(1);
+    }
+}
+
+fn cache() {
+    let config = CacheConfig::build(env::args()).unwrap_or_else(|err| {
+        handle_parsing_error(err);
+        process::exit(1);
+    });
+
+    if let Err(err) = adventofcode::cache_command(config) {
+        handle_app_error(&err);
+        process::exit(1);
+    }
+}
+
+fn repl() {
+    let config = ReplConfig::build(env::args()).unwrap_or_else(|err| {
+        handle_parsing_error(err);
+        process::exit(1);
+    });
+
+    if let Err(err) = adventofcode::repl::run(config) {
         handle_app_error(&err);
         process::exit(1);
     }
 }
 
 fn usage() {
-    eprintln!("Usage: {} <file_name>", env::args().next().unwrap());
+    let program = env::args().next().unwrap();
+    eprintln!("Usage: {} [--day=<n>] [options] [file_name]", program);
+    eprintln!("       {} repl [--day=<n>] [file_name]", program);
+    eprintln!("       {} cache clear [--cache=<dir>]", program);
+    eprintln!("       {} generate <day> [--seed=<n>] [--size=<n>] [--scale=<n>] [--output=<path>]", program);
 }
 
 fn handle_parsing_error(err: &str) {
@@ -0,0 +1,338 @@
+//! `repl`: load an input once, then switch days and parts, run the solvers
+//! and look at the parsed models.
+
+use std::{
+    fs,
+    io::{self, Write},
+    time::Instant,
+};
+
+use crate::{
+    common::{config::ReplConfig, readline::LineEditor},
+    day05, day07, day08, LATEST_DAY,
+};
+
+const HELP: &str = "\
+:day <n>         select the day
+:part <1|2|all>  select the part to run
+:load <path>     read the input from a file, then run
+:paste           type or paste an input, end it with :end, then run
+:run             run the selected parts on the input
+:show <model>    print a parsed model: tree (day 7), stacks (day 5), forest (day 8), input
+:help            this help
+:quit            leave, as does Ctrl-D";
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum Flow {
+    Continue,
+    Quit,
+}
+
+pub struct Repl {
+    day: u8,
+    /// `None` runs both parts.
+    part: Option<u8>,
+    input: String,
+    source: String,
+    /// Lines typed since `:paste`.
+    paste: Option<Vec<String>>,
+}
+
+impl Repl {
+    pub fn new(day: u8, input: String, source: String) -> Self {
+        return Self {
+            day,
+            part: None,
+            input,
+            source,
+            paste: None,
+        };
+    }
+
+    pub fn prompt(&self) -> String {
+        if self.paste.is_some() {
+            return "... ".to_string();
+        }
+        return match self.part {
+            Some(part) => format!("day {} part {}> ", self.day, part),
+            None => format!("day {}> ", self.day),
+        };
+    }
+
+    pub fn execute(&mut self, line: &str, out: &mut impl Write) -> Result<Flow, String> {
+        return self.try_execute(line, out).map_err(String::from);
+    }
+
+    fn try_execute(&mut self, line: &str, out: &mut impl Write) -> Result<Flow, ReplError> {
+        if let Some(lines) = self.paste.as_mut() {
+            if line.trim() != ":end" {
+                lines.push(line.to_string());
+                return Ok(Flow::Continue);
+            }
+            let mut input = lines.join("\n");
+            input.push('\n');
+            self.paste = None;
+            writeln!(out, "{} lines pasted", input.lines().count())?;
+            self.set_input(input, "pasted input".to_string());
+            self.run(out)?;
+            return Ok(Flow::Continue);
+        }
+
+        let line = line.trim();
+        if line.is_empty() {
+            return Ok(Flow::Continue);
+        }
+        let Some(command) = line.strip_prefix(':') else {
+            return Err(ReplError::Usage(format!(
+                "not a command: {}, try :help or :paste to type an input",
+                line
+            )));
+        };
+        let (command, argument) = match command.split_once(char::is_whitespace) {
+            Some((command, argument)) => (command, argument.trim()),
+            None => (command, ""),
+        };
+
+        match command {
+            "day" | "d" => {
+                let day = argument
+                    .parse()
+                    .ok()
+                    .filter(|day| (1..=LATEST_DAY).contains(day))
+                    .ok_or_else(|| {
+                        ReplError::Usage(format!("expected a day from 1 to {}", LATEST_DAY))
+                    })?;
+                self.day = day;
+                writeln!(out, "day {}", self.day)?;
+            }
+            "part" | "p" => {
+                self.part = match argument {
+                    "1" => Some(1),
+                    "2" => Some(2),
+                    "all" | "" => None,
+                    _ => return Err(ReplError::Usage("expected 1, 2 or all".to_string())),
+                };
+            }
+            "load" | "l" => {
+                if argument.is_empty() {
+                    return Err(ReplError::Usage("expected a file path".to_string()));
+                }
+                let input = fs::read_to_string(argument).map_err(|err| {
+                    ReplError::Usage(format!("could not read {}: {}", argument, err))
+                })?;
+                writeln!(out, "{} lines loaded from {}", input.lines().count(), argument)?;
+                self.set_input(input, argument.to_string());
+                self.run(out)?;
+            }
+            "paste" => {
+                self.paste = Some(Vec::new());
+                writeln!(out, "type or paste the input, end it with :end")?;
+            }
+            "run" | "r" => self.run(out)?,
+            "show" | "s" => {
+                let shown = self.show(argument).map_err(ReplError::Usage)?;
+                writeln!(out, "{}", shown)?;
+            }
+            "help" | "h" | "?" => writeln!(out, "{}", HELP)?,
+            "quit" | "q" | "exit" => return Ok(Flow::Quit),
+            _ => {
+                return Err(ReplError::Usage(format!(
+                    "unknown command :{}, try :help",
+                    command
+                )))
+            }
+        }
+        return Ok(Flow::Continue);
+    }
+
+    fn set_input(&mut self, input: String, source: String) {
+        self.input = input;
+        self.source = source;
+    }
+
+    fn run(&self, out: &mut impl Write) -> io::Result<()> {
+        if self.input.is_empty() {
+            return writeln!(out, "no input yet, use :load or :paste");
+        }
+
+        let parts = match self.part {
+            Some(part) => vec![part],
+            None => vec![1, 2],
+        };
+        for part in parts {
+            let start = Instant::now();
+            match crate::answer(self.day, part, &self.input) {
+                Ok(answer) => {
+                    writeln!(out, "  Part {}: {} ({:.2?})", part, answer, start.elapsed())?
+                }
+                Err(err) => writeln!(out, "  Part {}: error: {}", part, err)?,
+            }
+        }
+        return Ok(());
+    }
+
+    fn show(&self, model: &str) -> Result<String, String> {
+        return match model {
+            "tree" => day07::show_tree(&self.input),
+            "stacks" => day05::show_stacks(&self.input, self.part),
+            "forest" | "grid" => day08::show_forest(&self.input),
+            "input" => Ok(format!(
+                "{} lines, {} bytes from {}\n{}",
+                self.input.lines().count(),
+                self.input.len(),
+                self.source,
+                self.input.lines().take(10).collect::<Vec<_>>().join("\n")
+            )),
+            _ => Err(format!(
+                "unknown model {:?}, try tree, stacks, forest or input",
+                model
+            )),
+        };
+    }
+}
+
+enum ReplError {
+    Io(io::Error),
+    Usage(String),
+}
+
+impl From<io::Error> for ReplError {
+    fn from(err: io::Error) -> Self {
+        return ReplError::Io(err);
+    }
+}
+
+impl From<ReplError> for String {
+    fn from(err: ReplError) -> Self {
+        return err.to_string();
+    }
+}
+
+impl std::fmt::Display for ReplError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        return match self {
+            ReplError::Io(err) => write!(f, "output error: {}", err),
+            ReplError::Usage(message) => write!(f, "{}", message),
+        };
+    }
+}
+
+/// Reads commands until `:quit` or the end of input.
+pub fn run(config: ReplConfig) -> Result<(), String> {
+    let day = config.options.parse_value("day")?.unwrap_or(LATEST_DAY);
+    let (input, source) = match &config.file_path {
+        Some(path) => (
+            fs::read_to_string(path).map_err(|err| format!("could not read {}: {}", path, err))?,
+            path.clone(),
+        ),
+        None => (String::new(), String::new()),
+    };
+
+    let mut repl = Repl::new(day, input, source);
+    let mut editor = LineEditor::new();
+    let mut stdout = io::stdout();
+    println!("Advent of Code 2022, :help for the commands");
+    while let Some(line) = editor
+        .read_line(&repl.prompt())
+        .map_err(|err| format!("could not read input: {}", err))?
+    {
+        match repl.execute(&line, &mut stdout) {
+            Ok(Flow::Quit) => break,
+            Ok(Flow::Continue) => (),
+            Err(err) => eprintln!("error: {}", err),
+        }
+    }
+    return Ok(());
+}
+
+#[cfg(test)]
+mod test {
+    use super::{Flow, Repl};
+
+    fn execute_all(repl: &mut Repl, lines: &[&str]) -> String {
+        let mut out: Vec<u8> = Vec::new();
+        for line in lines {
+            if let Err(err) = repl.execute(line, &mut out) {
+                out.extend(format!("error: {}\n", err).bytes());
+            }
+        }
+        return String::from_utf8(out).unwrap();
+    }
+
+    #[test]
+    fn paste_example_and_run() {
+        let mut repl = Repl::new(9, String::new(), String::new());
+
+        let out = execute_all(
+            &mut repl,
+            &[":day 4", ":paste", "2-4,6-8", "2-8,3-7", "6-6,4-6", ":end"],
+        );
+        assert!(out.contains("3 lines pasted"), "{}", out);
+        assert!(out.contains("Part 1: 2 ("), "{}", out);
+        assert!(out.contains("Part 2: 2 ("), "{}", out);
+
+        let out = execute_all(&mut repl, &[":part 2", ":paste", "5-7,7-9", ":end"]);
+        assert!(out.contains("Part 2: 1 ("), "{}", out);
+        assert!(!out.contains("Part 1"), "{}", out);
+        assert_eq!(repl.prompt(), "day 4 part 2> ");
+    }
+
+    #[test]
+    fn show_models() {
+        let mut repl = Repl::new(5, String::new(), String::new());
+
+        let out = execute_all(
+            &mut repl,
+            &[
+                ":part 1",
+                ":paste",
+                "    [D]    ",
+                "[N] [C]    ",
+                "[Z] [M] [P]",
+                " 1   2   3 ",
+                "",
+                "move 1 from 2 to 1",
+                "",
+                ":end",
+                ":show stacks",
+            ],
+        );
+        assert!(out.contains("Part 1: DCP"), "{}", out);
+        assert!(
+            out.contains("3 stacks, 1 moves\n    [D]\n[N] [C]\n[Z] [M] [P]\n 1   2   3\n"),
+            "{}",
+            out
+        );
+        assert!(out.contains("after the part 1 moves:\n[D]\n[N] [C]\n[Z] [M] [P]\n"), "{}", out);
+    }
+
+    #[test]
+    fn errors_and_quit() {
+        let mut repl = Repl::new(1, String::new(), String::new());
+
+        let out = execute_all(
+            &mut repl,
+            &[
+                ":day 42",
+                ":frobnicate",
+                "hello",
+                ":run",
+                ":show tree",
+                ":day 7",
+                ":paste",
+                "$ cd /",
+                ":end",
+                ":show tree",
+            ],
+        );
+        assert!(out.contains("error: expected a day from 1 to 9"), "{}", out);
+        assert!(out.contains("error: unknown command :frobnicate"), "{}", out);
+        assert!(out.contains("error: not a command: hello"), "{}", out);
+        assert!(out.contains("no input yet"), "{}", out);
+        assert!(out.contains("error: no day 7 transcript loaded"), "{}", out);
+        assert!(out.contains("error: the transcript ends too early"), "{}", out);
+
+        let mut out: Vec<u8> = Vec::new();
+        assert_eq!(repl.execute(":quit", &mut out), Ok(Flow::Quit));
+    }
+}